@@ -210,4 +210,336 @@ pub enum VotingSystemError {
     
     #[msg("Confidence voting is still active.")]
     ConfidenceVotingStillActive,
+    
+    // NUEVOS ERRORES PARA SESSION KEYS
+    #[msg("Invalid session scopes. Only voting instructions can be delegated.")]
+    InvalidSessionScope,
+    
+    #[msg("Invalid session duration. Must be between 1 hour and 1 week (168 hours).")]
+    InvalidSessionDuration,
+    
+    #[msg("Invalid session max uses. Must be between 1 and 1000.")]
+    InvalidSessionMaxUses,
+    
+    #[msg("Session key cannot be the authority wallet.")]
+    InvalidSessionKey,
+    
+    #[msg("Session key is not allowed to sign this instruction.")]
+    SessionScopeNotAllowed,
+    
+    #[msg("Session key has expired.")]
+    SessionExpired,
+    
+    #[msg("Session key has no uses left.")]
+    SessionUsesExhausted,
+    
+    // NUEVOS ERRORES PARA CANCELACIÓN DE VOTACIONES
+    #[msg("Vote can only be cancelled before any ballots or within the grace period.")]
+    CancelWindowClosed,
+    
+    // NUEVOS ERRORES PARA FLUJO KNOWLEDGE COMPLETO
+    #[msg("Knowledge type voting requires a committed answer hash.")]
+    MissingAnswerHash,
+    
+    #[msg("Vote is not awaiting the answer reveal.")]
+    NotAwaitingReveal,
+    
+    #[msg("Revealed answer is too long. Maximum 200 characters.")]
+    AnswerTooLong,
+    
+    #[msg("Reveal deadline has not expired yet.")]
+    RevealDeadlineNotExpired,
+    
+    #[msg("Vote cannot be closed yet.")]
+    VoteNotClosable,
+    
+    // NUEVOS ERRORES PARA LIQUIDACIÓN DE BONUS KNOWLEDGE
+    #[msg("Knowledge reward already claimed for this participation.")]
+    RewardAlreadyClaimed,
+    
+    #[msg("Selected option is not the correct answer.")]
+    IncorrectAnswer,
+    
+    // NUEVOS ERRORES PARA TORNEOS
+    #[msg("Invalid number of tournament rounds. Must be between 1 and 20.")]
+    InvalidTournamentRounds,
+    
+    #[msg("All tournament rounds have already been created.")]
+    TournamentRoundsFull,
+    
+    #[msg("Tournament is not active.")]
+    TournamentNotActive,
+    
+    #[msg("Vote is not a round of this tournament.")]
+    RoundNotInTournament,
+    
+    #[msg("Round already scored for this participant.")]
+    RoundAlreadyScored,
+    
+    #[msg("Tournament scoring period has ended.")]
+    TournamentScoringClosed,
+    
+    #[msg("Tournament is still running.")]
+    TournamentStillRunning,
+    
+    #[msg("Prize recipients don't match the tournament standings.")]
+    InvalidPrizeRecipient,
+    
+    // NUEVOS ERRORES PARA BONUS POR RAPIDEZ
+    #[msg("Invalid speed bonus. Only for knowledge votes, with min_bonus <= max_bonus <= 10.")]
+    InvalidSpeedBonus,
+    
+    // NUEVOS ERRORES PARA VOTOS SELLADOS
+    #[msg("This vote is sealed. Use commit_vote instead.")]
+    SealedVoteRequiresCommit,
+    
+    #[msg("This vote is not sealed.")]
+    VoteNotSealed,
+    
+    #[msg("Ballot reveal window is not open.")]
+    BallotRevealClosed,
+    
+    #[msg("Ballot has already been revealed.")]
+    BallotAlreadyRevealed,
+    
+    #[msg("Revealed option and salt don't match the ballot commitment.")]
+    InvalidBallotReveal,
+    
+    #[msg("Ballot has not been revealed.")]
+    BallotNotRevealed,
+    
+    #[msg("Ballot reveal period has not ended yet.")]
+    BallotRevealNotEnded,
+    
+    // NUEVOS ERRORES PARA EXPLICACIONES DE RESPUESTAS
+    #[msg("Invalid URI. Must use ar://, ipfs:// or https:// and fit the maximum length.")]
+    InvalidUri,
+    
+    #[msg("An explanation URI requires the hash of its content.")]
+    MissingExplanationHash,
+    
+    // NUEVOS ERRORES PARA DISPUTAS DE RESPUESTAS
+    #[msg("Dispute bond is below the minimum.")]
+    DisputeBondTooLow,
+    
+    #[msg("Answers can only be disputed during the confidence phase.")]
+    DisputeWindowClosed,
+    
+    #[msg("Proposed answer must be a valid option different from the revealed one.")]
+    InvalidProposedAnswer,
+    
+    #[msg("Dispute is not open.")]
+    DisputeNotOpen,
+    
+    #[msg("Creator has already posted the dispute bond.")]
+    CreatorBondAlreadyPosted,
+    
+    #[msg("Dispute response period has ended.")]
+    DisputeResponseWindowClosed,
+    
+    #[msg("Creator can still respond to the dispute.")]
+    DisputeResponsePending,
+    
+    #[msg("Parties of a dispute cannot resolve it.")]
+    CannotResolveOwnDispute,
+    
+    // NUEVOS ERRORES PARA UPDATE_COMMUNITY
+    #[msg("Invalid penalty percentage. Must be between 0 and 100.")]
+    InvalidPenaltyPercentage,
+    
+    #[msg("No settings to update.")]
+    NoSettingsToUpdate,
+    
+    // NUEVOS ERRORES PARA AUTHORITY Y CO-ADMINS
+    #[msg("No pending authority transfer for this signer.")]
+    NoPendingAuthorityTransfer,
+    
+    #[msg("New authority is already the community authority.")]
+    AlreadyCommunityAuthority,
+    
+    #[msg("Maximum number of admins reached.")]
+    TooManyAdmins,
+    
+    #[msg("User is already an admin.")]
+    AlreadyAdmin,
+    
+    #[msg("User is not an admin.")]
+    NotAdmin,
+    
+    #[msg("The community authority cannot be demoted.")]
+    CannotDemoteAuthority,
+    
+    // NUEVOS ERRORES PARA CONSEJO DE ADMINS
+    #[msg("This action requires council approval.")]
+    CouncilApprovalRequired,
+    
+    #[msg("Invalid council members. Must be 1-7 distinct admins.")]
+    InvalidCouncilMembers,
+    
+    #[msg("Invalid council threshold. Must be between 1 and the number of members.")]
+    InvalidCouncilThreshold,
+    
+    #[msg("Signer is not a council member.")]
+    NotCouncilMember,
+    
+    #[msg("Council action has expired.")]
+    CouncilActionExpired,
+    
+    #[msg("Council action has not expired yet.")]
+    CouncilActionNotExpired,
+    
+    #[msg("Council member has already approved this action.")]
+    AlreadyApproved,
+    
+    #[msg("Council approval threshold not reached.")]
+    ThresholdNotReached,
+    
+    #[msg("Recipient doesn't match the approved action.")]
+    InvalidRecipient,
+    
+    // NUEVOS ERRORES PARA ARCHIVO Y CIERRE DE COMUNIDADES
+    #[msg("Community is archived.")]
+    CommunityArchived,
+    
+    #[msg("Community must be archived first.")]
+    CommunityNotArchived,
+    
+    #[msg("Community still has members. Close their memberships first.")]
+    CommunityHasMembers,
+    
+    // NUEVOS ERRORES PARA PERFIL DE COMUNIDAD
+    #[msg("Too many social links. Maximum is 5.")]
+    TooManySocialLinks,
+    
+    #[msg("A rules URI requires the hash of the rules document.")]
+    MissingRulesHash,
+    
+    // NUEVOS ERRORES PARA TIERS DE COMUNIDAD
+    #[msg("Private communities always require approval.")]
+    PrivateCommunityRequiresApproval,
+    
+    #[msg("This feature requires a Premium community.")]
+    PremiumTierRequired,
+    
+    #[msg("Community has reached its member limit.")]
+    CommunityFull,
+    
+    // NUEVOS ERRORES PARA SALIDA VOLUNTARIA
+    #[msg("Community authority must transfer authority before leaving.")]
+    AuthorityCannotLeave,
+    
+    #[msg("The last admin cannot leave the community.")]
+    LastAdminCannotLeave,
+    
+    // NUEVOS ERRORES PARA INVITACIONES
+    #[msg("Invalid invite duration. Must be between 1 hour and 90 days.")]
+    InvalidInviteDuration,
+    
+    #[msg("Invite list has expired or was revoked.")]
+    InviteListInactive,
+    
+    #[msg("Invite proof does not match the invite list.")]
+    InvalidInviteProof,
+    
+    // NUEVOS ERRORES PARA CUOTAS DE MEMBRESÍA
+    #[msg("Invalid membership period. Must be 0 or between 1 day and 1 year.")]
+    InvalidMembershipPeriod,
+    
+    #[msg("Membership has expired. Renew it to participate.")]
+    MembershipExpired,
+    
+    #[msg("This community does not charge membership dues.")]
+    NoMembershipDues,
+    
+    #[msg("Invalid number of renewal periods. Must be between 1 and 12.")]
+    InvalidRenewalPeriods,
+    
+    #[msg("Invalid membership fee. Maximum is 100 SOL per period.")]
+    InvalidMembershipFee,
+    
+    // NUEVOS ERRORES PARA LISTA DE ESPERA
+    #[msg("Member cap exceeds the community tier limit.")]
+    InvalidMemberCap,
+    
+    #[msg("Community has free seats. Join directly instead.")]
+    CommunityNotFull,
+    
+    #[msg("Waitlist is full.")]
+    WaitlistFull,
+    
+    #[msg("User is already on the waitlist.")]
+    AlreadyOnWaitlist,
+    
+    #[msg("User is not on the waitlist.")]
+    NotOnWaitlist,
+    
+    #[msg("Waitlist is empty.")]
+    WaitlistEmpty,
+    
+    // NUEVOS ERRORES PARA ROLES PERSONALIZADOS
+    #[msg("Invalid permission flags.")]
+    InvalidPermissions,
+    
+    #[msg("Role name must be between 1 and 32 characters.")]
+    InvalidRoleName,
+    
+    #[msg("Role does not match the membership's assigned role.")]
+    InvalidCommunityRole,
+    
+    // NUEVOS ERRORES PARA GESTIÓN DE MODERADORES
+    #[msg("Community already has the maximum number of moderators (5).")]
+    TooManyModerators,
+    
+    #[msg("User is already a moderator or admin.")]
+    AlreadyModerator,
+    
+    #[msg("User is not a moderator.")]
+    NotModerator,
+    
+    // NUEVOS ERRORES PARA SUB-COMUNIDADES Y FEDERACIONES
+    #[msg("Sub-communities cannot have their own sub-communities.")]
+    NestedSubCommunity,
+    
+    #[msg("Parent community already has the maximum number of sub-communities (16).")]
+    TooManySubCommunities,
+    
+    #[msg("Parent community account does not match.")]
+    InvalidParentCommunity,
+    
+    #[msg("Community still has sub-communities.")]
+    CommunityHasChildren,
+    
+    #[msg("Federated votes must include every sub-community exactly once.")]
+    InvalidFederation,
+    
+    #[msg("Only opinion votes without ballots can be federated.")]
+    CannotFederateVote,
+    
+    // NUEVOS ERRORES PARA REENVÍO DE SOLICITUDES
+    #[msg("A membership request is already pending.")]
+    RequestAlreadyPending,
+    
+    #[msg("Request was rejected recently. Wait for the community cooldown.")]
+    RequestCooldownActive,
+    
+    #[msg("Membership request was already approved.")]
+    RequestAlreadyApproved,
+    
+    #[msg("Invalid request cooldown. Maximum is 90 days.")]
+    InvalidRequestCooldown,
+    
+    // NUEVOS ERRORES PARA REEMBOLSO DE VOTACIONES CANCELADAS
+    #[msg("Vote is not cancelled or has no ballots left to refund.")]
+    VoteNotCancelled,
+    
+    // NUEVOS ERRORES PARA VALIDACIÓN DE RESPUESTAS KNOWLEDGE
+    #[msg("The knowledge answer was not validated by the community.")]
+    AnswerNotValidated,
+    
+    #[msg("Dispute is still open.")]
+    DisputeStillOpen,
+    
+    // NUEVOS ERRORES PARA ROTACIÓN DEL CONSEJO
+    #[msg("Council member is still an active admin.")]
+    CouncilMemberStillActive,
 }
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+// Eventos del ciclo de vida de las solicitudes de membresía
+// (el account se reutiliza o se cierra, así que el historial queda en los logs)
+
+#[event]
+pub struct MembershipRequested {
+    pub community: Pubkey,
+    pub user: Pubkey,
+    pub message: String,
+    pub is_resubmission: bool,      // Reutiliza el PDA de una solicitud anterior
+    pub requested_at: i64,
+}
+
+#[event]
+pub struct MembershipRequestApproved {
+    pub community: Pubkey,
+    pub user: Pubkey,
+    pub reviewed_by: Pubkey,
+    pub admin_notes: String,
+    pub reviewed_at: i64,
+}
+
+#[event]
+pub struct MembershipRequestRejected {
+    pub community: Pubkey,
+    pub user: Pubkey,
+    pub reviewed_by: Pubkey,
+    pub admin_notes: String,
+    pub reviewed_at: i64,
+    pub can_resubmit_at: i64,       // Fin del cooldown de la comunidad
+}
+
+#[event]
+pub struct MembershipRequestCancelled {
+    pub community: Pubkey,
+    pub user: Pubkey,
+    pub cancelled_at: i64,
+}
@@ -1,16 +1,23 @@
 use anchor_lang::prelude::*;
-use solana_program::hash::hash;
 
 pub mod state;
 pub mod errors;
+pub mod events;
 
-use state::{User, Community, Vote, VoteType, VoteStatus, Membership, Participation, FeePool, FeeTier, RewardRecord};
+use state::{User, Community, CommunitySettings, CommunityProfile, CommunityProfileData, Vote, VoteType, VoteStatus, VoteSettings, Membership, Participation, ConfidenceParticipation, FeePool, FeeTier, CommunityTier, RewardRecord};
+use state::{InviteList, Waitlist, WaitlistEntry};
+use state::{CommunityRole, MEMBER_GRANTABLE_PERMISSIONS, PERMISSION_CREATE_VOTE, PERMISSION_REVIEW_REPORTS, PERMISSION_BAN, PERMISSION_APPROVE_MEMBERS, PERMISSION_WITHDRAW, PERMISSION_MANAGE_CATEGORIES};
 use state::{GlobalLeaderboard, CommunityLeaderboard, LeaderboardEntry}; // TAREA 2.6: Leaderboards
 use state::membership::{UserRole, BanRecord, BanType, ModerationLog, ModerationAction, MembershipRequest, MembershipRequestStatus};
 use state::moderation::{ReportType, ReportStatus};
 use state::reports::{Report, ReportCounter, Appeal, AppealStatus};
 use state::categories::{VotingCategory, CustomCategory, CategorySubscription};
+use state::session::{SessionKey, SESSION_SCOPE_CAST_VOTE, SESSION_SCOPE_VOTE_CONFIDENCE};
+use state::tournament::{Tournament, TournamentScore, TournamentStanding, TournamentStatus};
+use state::dispute::{AnswerDispute, DisputeStatus};
+use state::council::{AdminCouncil, PendingAction, CouncilAction};
 use errors::VotingSystemError;
+use events::{MembershipRequested, MembershipRequestApproved, MembershipRequestRejected, MembershipRequestCancelled};
 
 declare_id!("98eSBn9oRdJcPzFUuRMgktewygF6HfkwiCQUJuJBw1z");
 
@@ -102,6 +109,84 @@ pub mod voting_system {
         msg!("Votes received: {}", vote.total_votes);
         msg!("Closed at: {}", clock.unix_timestamp);
         
+        ctx.accounts.community.moderation_log_count += 1;
+        
+        Ok(())
+    }
+    
+    pub fn cancel_vote(ctx: Context<CancelVote>) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let community = &mut ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        // Solo votaciones activas, sin votos o dentro del periodo de gracia
+        require!(vote.status == VoteStatus::Active, VotingSystemError::VoteNotActive);
+        require!(vote.can_be_cancelled(clock.unix_timestamp), VotingSystemError::CancelWindowClosed);
+        
+        // Reembolso del fee menos la penalización configurada por la comunidad
+        // Si la comunidad está archivada el vault ya se liquidó a la authority: no hay nada que devolver
+        let penalty = (vote.fee_per_vote * community.cancel_penalty_percentage as u64) / 100;
+        let refund = if community.is_archived() { 0 } else { vote.fee_per_vote - penalty };
+        
+        if refund > 0 {
+            require!(
+                vault_available_balance(&ctx.accounts.vault)? >= refund,
+                VotingSystemError::InsufficientFunds
+            );
+            
+            transfer_from_vault(
+                &ctx.accounts.vault,
+                &ctx.accounts.creator.to_account_info(),
+                &ctx.accounts.system_program,
+                community.key(),
+                ctx.bumps.vault,
+                refund,
+            )?;
+            
+            if let Some(fee_pool) = &mut ctx.accounts.fee_pool {
+                fee_pool.total_collected = fee_pool.total_collected.saturating_sub(refund);
+            }
+        }
+        
+        vote.status = VoteStatus::Cancelled;
+        community.total_votes = community.total_votes.saturating_sub(1);
+        
+        msg!("🗑️ Vote cancelled by creator!");
+        msg!("Vote: {}", vote.question);
+        msg!("Creator: {}", vote.creator);
+        msg!("Votes received: {}", vote.total_votes);
+        msg!("Refund: {} lamports (penalty: {} lamports, {}%)", 
+             refund, penalty, community.cancel_penalty_percentage);
+        msg!("Cancelled at: {}", clock.unix_timestamp);
+        
+        // Sin votos el rent vuelve ya al creator; con votos cada votante recupera
+        // su participation (reclaim_cancelled_ballot) y después se usa close_vote
+        if vote.total_votes == 0 {
+            ctx.accounts.vote.close(ctx.accounts.creator.to_account_info())?;
+        } else {
+            msg!("Ballots pending refund: {}", vote.total_votes);
+        }
+        
+        Ok(())
+    }
+    
+    pub fn reclaim_cancelled_ballot(ctx: Context<ReclaimCancelledBallot>) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let user = &mut ctx.accounts.user;
+        
+        // Deshacer el voto: deja de contar y se revierte el punto de participación
+        vote.total_votes = vote.total_votes.saturating_sub(1);
+        vote.participants.retain(|participant| *participant != user.wallet);
+        user.total_votes_cast = user.total_votes_cast.saturating_sub(1);
+        user.reputation_points = user.reputation_points.saturating_sub(1);
+        user.update_voting_weight();
+        
+        // El account se cierra (close = wallet) y el rent vuelve al votante
+        msg!("↩️ Ballot reclaimed from cancelled vote!");
+        msg!("Vote: {}", vote.question);
+        msg!("Voter: {}", user.wallet);
+        msg!("Ballots pending refund: {}", vote.total_votes);
+        
         Ok(())
     }
     
@@ -120,12 +205,18 @@ pub mod voting_system {
         require!(icon.len() <= 10, VotingSystemError::IconTooLong);
         
         let custom_category = &mut ctx.accounts.custom_category;
-        let admin_membership = &ctx.accounts.admin_membership;
+        let admin_membership = &mut ctx.accounts.admin_membership;
         let community = &ctx.accounts.community;
         let clock = Clock::get()?;
         
-        // Solo admins pueden crear categorías personalizadas
-        require!(admin_membership.is_admin(), VotingSystemError::InsufficientPermissions);
+        // Admins o roles con MANAGE_CATEGORIES
+        require!(
+            has_permission(admin_membership, &ctx.accounts.custom_role, community.member_permissions, PERMISSION_MANAGE_CATEGORIES)?,
+            VotingSystemError::InsufficientPermissions
+        );
+        
+        // Feature exclusiva del tier Premium
+        require!(community.tier.allows_custom_categories(), VotingSystemError::PremiumTierRequired);
         
         // Inicializar categoría personalizada
         custom_category.community = community.key();
@@ -303,12 +394,15 @@ pub mod voting_system {
         require!(notes.len() <= 200, VotingSystemError::NotesTooLong);
         
         let report = &mut ctx.accounts.report;
-        let moderator_membership = &ctx.accounts.moderator_membership;
+        let moderator_membership = &mut ctx.accounts.moderator_membership;
         let clock = Clock::get()?;
         
         // Validaciones
         require!(report.status == ReportStatus::Pending, VotingSystemError::ReportNotPending);
-        require!(moderator_membership.can_moderate(), VotingSystemError::OnlyModeratorsCanReview);
+        require!(
+            has_permission(moderator_membership, &ctx.accounts.custom_role, 0, PERMISSION_REVIEW_REPORTS)?,
+            VotingSystemError::OnlyModeratorsCanReview
+        );
         
         // Actualizar reporte
         report.status = ReportStatus::Reviewed;
@@ -332,6 +426,8 @@ pub mod voting_system {
         msg!("Moderator: {}", moderator_membership.user);
         msg!("Notes: {}", notes);
         
+        ctx.accounts.community.moderation_log_count += 1;
+        
         Ok(())
     }
     
@@ -425,6 +521,8 @@ pub mod voting_system {
             msg!("🚫 Appeal denied - ban remains active");
         }
         
+        ctx.accounts.community.moderation_log_count += 1;
+        
         Ok(())
     }
     
@@ -456,6 +554,7 @@ pub mod voting_system {
     ) -> Result<()> {
         let membership = &mut ctx.accounts.membership;
         let admin_membership = &ctx.accounts.admin_membership;
+        let community = &mut ctx.accounts.community;
         let clock = Clock::get()?;
         
         // Solo admins pueden asignar moderadores
@@ -463,8 +562,15 @@ pub mod voting_system {
         
         // No se puede asignar a usuarios baneados
         require!(membership.role != UserRole::Banned, VotingSystemError::UserBanned);
+        require!(membership.is_active, VotingSystemError::NotCommunityMember);
+        require!(membership.role == UserRole::Member, VotingSystemError::AlreadyModerator);
+        require!(
+            community.moderators.len() < Community::MAX_MODERATORS,
+            VotingSystemError::TooManyModerators
+        );
         
         membership.role = UserRole::Moderator;
+        community.moderators.push(membership.user);
         
         // Crear log de moderación
         let moderation_log = &mut ctx.accounts.moderation_log;
@@ -481,6 +587,50 @@ pub mod voting_system {
         msg!("User: {}", membership.user);
         msg!("Community: {}", membership.community);
         msg!("Assigned by: {}", admin_membership.user);
+        msg!("Total moderators: {}", community.moderators.len());
+        
+        ctx.accounts.community.moderation_log_count += 1;
+        
+        Ok(())
+    }
+    
+    pub fn remove_moderator(
+        ctx: Context<RemoveModerator>,
+        reason: String,
+    ) -> Result<()> {
+        require!(reason.len() <= 200, VotingSystemError::ReasonTooLong);
+        
+        let membership = &mut ctx.accounts.membership;
+        let admin_membership = &ctx.accounts.admin_membership;
+        let community = &mut ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        // Solo admins pueden degradar moderadores (los admins van por demote_admin)
+        require!(admin_membership.is_admin(), VotingSystemError::InsufficientPermissions);
+        require!(membership.role == UserRole::Moderator, VotingSystemError::NotModerator);
+        
+        membership.role = UserRole::Member;
+        community.unlist_moderator(&membership.user);
+        
+        // Crear log de moderación
+        let moderation_log = &mut ctx.accounts.moderation_log;
+        moderation_log.community = membership.community;
+        moderation_log.moderator = admin_membership.user;
+        moderation_log.target_user = Some(membership.user);
+        moderation_log.target_vote = None;
+        moderation_log.action = ModerationAction::RemoveModerator;
+        moderation_log.reason = reason.clone();
+        moderation_log.executed_at = clock.unix_timestamp;
+        moderation_log.bump = ctx.bumps.moderation_log;
+        
+        msg!("⬇️ Moderator removed successfully!");
+        msg!("User: {}", membership.user);
+        msg!("Community: {}", membership.community);
+        msg!("Reason: {}", reason);
+        msg!("Removed by: {}", admin_membership.user);
+        msg!("Total moderators: {}", community.moderators.len());
+        
+        ctx.accounts.community.moderation_log_count += 1;
         
         Ok(())
     }
@@ -494,12 +644,15 @@ pub mod voting_system {
         require!(reason.len() <= 200, VotingSystemError::ReasonTooLong);
         
         let membership = &mut ctx.accounts.membership;
-        let moderator_membership = &ctx.accounts.moderator_membership;
+        let moderator_membership = &mut ctx.accounts.moderator_membership;
         let ban_record = &mut ctx.accounts.ban_record;
         let clock = Clock::get()?;
         
-        // Solo moderadores y admins pueden banear
-        require!(moderator_membership.can_moderate(), VotingSystemError::InsufficientPermissions);
+        // Moderadores, admins o roles con BAN
+        require!(
+            has_permission(moderator_membership, &ctx.accounts.custom_role, 0, PERMISSION_BAN)?,
+            VotingSystemError::InsufficientPermissions
+        );
         
         // No se puede banear a otros moderadores o admins
         require!(!membership.is_moderator(), VotingSystemError::CannotBanModerator);
@@ -518,6 +671,7 @@ pub mod voting_system {
         // Actualizar membership
         membership.role = UserRole::Banned;
         membership.is_active = false;
+        membership.custom_role = None;
         
         // Crear registro de ban
         ban_record.user = membership.user;
@@ -549,6 +703,8 @@ pub mod voting_system {
             msg!("Expires at: {}", expires);
         }
         
+        ctx.accounts.community.moderation_log_count += 1;
+        
         Ok(())
     }
 
@@ -556,27 +712,39 @@ pub mod voting_system {
         ctx: Context<WithdrawFees>,
         amount: u64,
     ) -> Result<()> {
-        let community = &mut ctx.accounts.community;
-        let admin_membership = &ctx.accounts.admin_membership;
+        let community = &ctx.accounts.community;
+        let admin_membership = &mut ctx.accounts.admin_membership;
         
-        // Solo el admin de la comunidad puede retirar fees
-        require!(admin_membership.is_admin(), VotingSystemError::InsufficientPermissions);
-        require!(community.authority == admin_membership.user, VotingSystemError::InsufficientPermissions);
+        // La authority o un rol con WITHDRAW (tesorero)
+        require!(
+            community.authority == admin_membership.user ||
+                has_permission(admin_membership, &ctx.accounts.custom_role, 0, PERMISSION_WITHDRAW)?,
+            VotingSystemError::InsufficientPermissions
+        );
         
-        // Verificar que hay suficientes fees para retirar
-        require!(community.fee_collected >= amount, VotingSystemError::InsufficientFunds);
+        // Con consejo, los retiros pasan por propose_council_action
+        require!(community.council.is_none(), VotingSystemError::CouncilApprovalRequired);
         
-        // Transferir SOL de community account al admin
-        **community.to_account_info().try_borrow_mut_lamports()? -= amount;
-        **ctx.accounts.admin.to_account_info().try_borrow_mut_lamports()? += amount;
+        // Verificar que hay suficientes fees para retirar (sin tocar el mínimo rent-exempt)
+        require!(
+            vault_available_balance(&ctx.accounts.vault)? >= amount,
+            VotingSystemError::InsufficientFunds
+        );
         
-        // Actualizar contador de fees
-        community.fee_collected -= amount;
+        // Transferir SOL del vault al admin firmando con las seeds del vault
+        transfer_from_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program,
+            community.key(),
+            ctx.bumps.vault,
+            amount,
+        )?;
         
         msg!("💰 Fees withdrawn successfully!");
         msg!("Admin: {}", admin_membership.user);
         msg!("Amount: {} lamports ({} SOL)", amount, amount as f64 / 1_000_000_000.0);
-        msg!("Remaining fees: {} lamports", community.fee_collected);
+        msg!("Remaining fees: {} lamports", vault_available_balance(&ctx.accounts.vault)?);
         
         Ok(())
     }
@@ -683,6 +851,7 @@ pub mod voting_system {
         // Desactivar membership
         membership.is_active = false;
         membership.role = UserRole::Member; // Reset role
+        membership.custom_role = None;
         
         // Actualizar stats de la comunidad
         let community = &mut ctx.accounts.community;
@@ -706,6 +875,8 @@ pub mod voting_system {
         msg!("Removed by: {}", admin_membership.user);
         msg!("New total members: {}", community.total_members);
         
+        ctx.accounts.community.moderation_log_count += 1;
+        
         Ok(())
     }
     
@@ -760,6 +931,7 @@ pub mod voting_system {
         category: u8,
         quorum_percentage: u8,
         requires_approval: bool,
+        tier: CommunityTier,
     ) -> Result<()> {
         // DEBUGGING - Log de valores recibidos
         msg!("🔍 DEBUGGING create_community - Valores recibidos:");
@@ -767,807 +939,4842 @@ pub mod voting_system {
         msg!("- category: {}", category);
         msg!("- quorum_percentage: {}", quorum_percentage);
         msg!("- requires_approval: {}", requires_approval);
+        msg!("- tier: {:?}", tier);
         
         require!(name.len() <= 50, VotingSystemError::NameTooLong);
         
+        // === SUB-COMUNIDAD ===
+        // La crea un admin de la padre; hereda tier y reglas de membresía
+        let parent_key = match &ctx.accounts.parent {
+            Some(parent) => {
+                let parent_admin = ctx.accounts.parent_admin_membership.as_ref()
+                    .ok_or(VotingSystemError::InsufficientPermissions)?;
+                require!(parent_admin.community == parent.key(), VotingSystemError::InvalidParentCommunity);
+                require!(parent_admin.user == ctx.accounts.authority.key(), VotingSystemError::InsufficientPermissions);
+                require!(parent_admin.is_admin(), VotingSystemError::InsufficientPermissions);
+                require!(parent.is_active, VotingSystemError::CommunityInactive);
+                require!(!parent.is_sub_community(), VotingSystemError::NestedSubCommunity);
+                require!(parent.child_count < Community::MAX_CHILDREN, VotingSystemError::TooManySubCommunities);
+                Some(parent.key())
+            },
+            None => None,
+        };
+        let tier = ctx.accounts.parent.as_ref().map_or(tier, |parent| parent.tier);
+        
         // DEBUGGING - Verificación de tipos y valores
         msg!("🔬 Tipo de quorum_percentage: u8");
         msg!("🔬 Valor como u16: {}", quorum_percentage as u16);
         msg!("🔬 Valor como u32: {}", quorum_percentage as u32);
         
-        // Validación explícita del quorum con mejor manejo de errores
-        match quorum_percentage {
-            1..=100 => {
-                msg!("✅ Quorum válido: {}%", quorum_percentage);
-            }
-            0 => {
-                msg!("❌ ERROR: Quorum inválido - 0% no está permitido");
-                return Err(VotingSystemError::InvalidQuorum.into());
-            }
-            _ => {
-                msg!("❌ ERROR: Quorum inválido - {}% excede el máximo de 100%", quorum_percentage);
-                return Err(VotingSystemError::InvalidQuorum.into());
-            }
-        }
+        // Mismas reglas que update_community
+        validate_community_settings(category, quorum_percentage)?;
+        msg!("✅ Quorum válido: {}%", quorum_percentage);
+        
+        // === FEE DE CREACIÓN SEGÚN TIER ===
+        // Va al FeePool global (no al vault de la comunidad)
+        let creation_fee = tier.get_creation_fee();
+        let fee_transfer = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.fee_pool.to_account_info(),
+        };
+        
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            fee_transfer,
+        );
+        
+        anchor_lang::system_program::transfer(cpi_context, creation_fee)?;
+        ctx.accounts.fee_pool.total_collected += creation_fee;
         
         let community = &mut ctx.accounts.community;
         let clock = Clock::get()?;
         
         community.authority = ctx.accounts.authority.key();
+        community.pending_authority = None;
+        community.admin_count = 1; // Creator
         community.moderators = Vec::new();
         community.name = name.clone();
         community.category = category;
         community.quorum_percentage = quorum_percentage;
         community.total_members = 1; // Creator is first member
+        community.max_members = None;
+        community.waitlist_count = 0;
         community.total_votes = 0;
-        community.fee_collected = 0;
         community.created_at = clock.unix_timestamp;
         community.is_active = true;
-        community.requires_approval = requires_approval;
+        community.requires_approval = requires_approval || tier.forces_approval();
+        community.tier = tier;
+        community.cancel_penalty_percentage = Community::DEFAULT_CANCEL_PENALTY_PERCENTAGE;
+        community.membership_fee = 0;
+        community.membership_period = 0;
+        community.member_permissions = Community::DEFAULT_MEMBER_PERMISSIONS;
+        community.request_cooldown = Community::DEFAULT_REQUEST_COOLDOWN;
+        community.council = None;
+        community.archived_at = None;
+        community.parent = parent_key;
+        community.child_count = 0;
+        community.moderation_log_count = 0;
         community.bump = ctx.bumps.community;
         
-        msg!("Community '{}' created by {}", community.name, community.authority);
-        msg!("Requires approval: {}", requires_approval);
-        msg!("Final quorum_percentage stored: {}", community.quorum_percentage);
-        Ok(())
-    }
-
-    pub fn create_voting(
-        ctx: Context<CreateVoting>,
-        question: String,
-        options: Vec<String>,
-        vote_type: VoteType,
-        correct_answer: Option<u8>,
-        deadline_hours: u32,
-        quorum_required: u64,
-        quorum_percentage: Option<u8>,
-        use_percentage_quorum: bool,
-    ) -> Result<()> {
-        // === VALIDACIONES BÁSICAS ===
-        require!(question.len() > 0 && question.len() <= 200, VotingSystemError::QuestionTooLong);
-        require!(options.len() >= 2 && options.len() <= 4, VotingSystemError::InvalidOptionsCount);
-        require!(deadline_hours >= 1 && deadline_hours <= 168, VotingSystemError::InvalidDeadline); // 1 hora a 1 semana
-        
-        // Validaciones de quorum
-        if use_percentage_quorum {
-            require!(quorum_percentage.is_some(), VotingSystemError::MissingQuorumPercentage);
-            let percentage = quorum_percentage.unwrap();
-            require!(percentage > 0 && percentage <= 100, VotingSystemError::InvalidQuorumPercentage);
-        } else {
-            require!(quorum_required > 0, VotingSystemError::InvalidQuorum);
-        }
-        
-        // Validar longitud de cada opción
-        for option in &options {
-            require!(option.len() > 0 && option.len() <= 50, VotingSystemError::OptionTooLong);
-        }
-        
-        // Validaciones específicas para Knowledge type
-        if vote_type == VoteType::Knowledge {
-            require!(correct_answer.is_some(), VotingSystemError::MissingCorrectAnswer);
-            let answer = correct_answer.unwrap();
-            require!((answer as usize) < options.len(), VotingSystemError::InvalidCorrectAnswer);
+        // Reglas de membresía heredadas de la padre: es una copia (snapshot), los cambios
+        // posteriores en la padre se propagan con sync_parent_rules
+        if let Some(parent) = ctx.accounts.parent.as_mut() {
+            inherit_parent_rules(community, parent);
+            parent.child_count += 1;
+            
+            msg!("🌳 Sub-community of '{}' ({} children)", parent.name, parent.child_count);
         }
         
-        // === SISTEMA DE FEES DINÁMICO ===
-        // Calcular fee basado en la reputación del usuario
-        let user = &ctx.accounts.user;
-        let fee_tier = FeeTier::from_reputation(user.reputation_points);
-        let voting_fee = fee_tier.get_voting_fee();
-        
-        // Solo transferir si el fee es mayor a 0
-        if voting_fee > 0 {
-            // Transferir fee del creator al community account
-            let fee_transfer = anchor_lang::system_program::Transfer {
-                from: ctx.accounts.creator.to_account_info(),
-                to: ctx.accounts.community.to_account_info(),
+        // Dejar el vault rent-exempt desde el inicio para que cualquier fee pueda entrar
+        let rent_minimum = Rent::get()?.minimum_balance(0);
+        let vault_funding = rent_minimum.saturating_sub(ctx.accounts.vault.lamports());
+        if vault_funding > 0 {
+            let vault_transfer = anchor_lang::system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
             };
             
             let cpi_context = CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
-                fee_transfer,
+                vault_transfer,
             );
             
-            anchor_lang::system_program::transfer(cpi_context, voting_fee)?;
-            
-            // Actualizar fee pool si existe
-            if let Some(fee_pool) = &mut ctx.accounts.fee_pool {
-                fee_pool.total_collected += voting_fee;
-            }
+            anchor_lang::system_program::transfer(cpi_context, vault_funding)?;
         }
         
-        // === INICIALIZACIÓN DE VOTE ACCOUNT ===
-        let vote = &mut ctx.accounts.vote;
+        // El creator es el primer miembro, con rol Admin
+        let creator_membership = &mut ctx.accounts.creator_membership;
+        creator_membership.user = ctx.accounts.authority.key();
+        creator_membership.community = community.key();
+        creator_membership.role = UserRole::Admin;
+        creator_membership.joined_at = clock.unix_timestamp;
+        creator_membership.is_active = true;
+        creator_membership.expires_at = None; // La authority no paga cuotas
+        creator_membership.custom_role = None;
+        creator_membership.bump = ctx.bumps.creator_membership;
+        
+        msg!("Community '{}' created by {}", community.name, community.authority);
+        msg!("Requires approval: {}", community.requires_approval);
+        msg!("Tier: {:?} (creation fee: {} lamports)", tier, creation_fee);
+        msg!("Final quorum_percentage stored: {}", community.quorum_percentage);
+        Ok(())
+    }
+    
+    pub fn update_community(
+        ctx: Context<UpdateCommunity>,
+        settings: CommunitySettings,
+        reason: String,
+    ) -> Result<()> {
+        require!(reason.len() <= 200, VotingSystemError::ReasonTooLong);
+        
         let community = &mut ctx.accounts.community;
+        let admin_membership = &ctx.accounts.admin_membership;
         let clock = Clock::get()?;
         
-        vote.community = community.key();
-        vote.creator = ctx.accounts.creator.key();
-        vote.question = question.clone();
-        vote.vote_type = vote_type;
-        vote.options = options.clone();
-        vote.correct_answer = correct_answer;
-        vote.participants = Vec::new();
-        vote.results = vec![0; options.len()];
-        vote.total_votes = 0;
-        vote.quorum_required = quorum_required;
-        vote.quorum_percentage = quorum_percentage;
-        vote.use_percentage_quorum = use_percentage_quorum;
-        vote.deadline = clock.unix_timestamp + (deadline_hours as i64 * 3600);
-        vote.status = VoteStatus::Active;
-        vote.fee_per_vote = voting_fee;
-        vote.created_at = clock.unix_timestamp;
-        vote.bump = ctx.bumps.vote;
+        // Con consejo, los cambios pasan por propose_council_action
+        require!(community.council.is_none(), VotingSystemError::CouncilApprovalRequired);
         
-        // === ACTUALIZAR ESTADÍSTICAS DE COMUNIDAD ===
-        community.total_votes += 1;
-        community.fee_collected += voting_fee;
+        apply_community_settings(community, &settings)?;
         
-        // === LOGS PARA DEBUGGING ===
-        msg!("✅ Voting created successfully!");
-        msg!("Question: {}", question);
-        msg!("Options: {:?}", options);
-        msg!("Type: {:?}", vote_type);
-        msg!("Creator: {}", ctx.accounts.creator.key());
-        msg!("Community: {}", community.name);
-        msg!("Fee collected: {} lamports (Tier: {:?})", voting_fee, fee_tier);
-        msg!("Deadline: {} hours from now", deadline_hours);
+        // Crear log de moderación
+        let moderation_log = &mut ctx.accounts.moderation_log;
+        moderation_log.community = community.key();
+        moderation_log.moderator = admin_membership.user;
+        moderation_log.target_user = None;
+        moderation_log.target_vote = None;
+        moderation_log.action = ModerationAction::UpdateCommunity;
+        moderation_log.reason = reason.clone();
+        moderation_log.executed_at = clock.unix_timestamp;
+        moderation_log.bump = ctx.bumps.moderation_log;
         
-        if use_percentage_quorum {
-            msg!("Quorum: {}% of members", quorum_percentage.unwrap());
-        } else {
-            msg!("Quorum: {} absolute votes", quorum_required);
-        }
+        msg!("✅ Community updated by {}", admin_membership.user);
+        msg!("Reason: {}", reason);
+        
+        ctx.accounts.community.moderation_log_count += 1;
         
         Ok(())
     }
-
-    pub fn cast_vote(
-        ctx: Context<CastVote>,
-        option_selected: u8,
-    ) -> Result<()> {
-        // === VALIDACIONES CRÍTICAS ===
-        let vote = &mut ctx.accounts.vote;
-        let membership = &ctx.accounts.membership;
-        let clock = Clock::get()?;
+    
+    // Volver a copiar las reglas de membresía de la padre en una sub-comunidad
+    pub fn sync_parent_rules(ctx: Context<SyncParentRules>) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let parent = &ctx.accounts.parent;
         
-        // 1. Verificar que la votación esté activa
-        require!(vote.status == VoteStatus::Active, VotingSystemError::VoteNotActive);
+        inherit_parent_rules(community, parent);
         
-        // 2. Verificar deadline
-        require!(clock.unix_timestamp < vote.deadline, VotingSystemError::VoteExpired);
+        msg!("🌳 Sub-community rules synced with '{}'", parent.name);
+        msg!("Requires approval: {}", community.requires_approval);
+        msg!("Membership fee: {} lamports every {} seconds", community.membership_fee, community.membership_period);
+        msg!("Member permissions: {:#06b}", community.member_permissions);
+        msg!("Request cooldown: {} seconds", community.request_cooldown);
         
-        // 3. Verificar opción válida
-        require!((option_selected as usize) < vote.options.len(), VotingSystemError::InvalidOption);
+        Ok(())
+    }
+    
+    // Crear o reemplazar el perfil de la comunidad (fuente de verdad on-chain para el frontend)
+    pub fn set_community_profile(
+        ctx: Context<SetCommunityProfile>,
+        data: CommunityProfileData,
+    ) -> Result<()> {
+        require!(
+            data.description.len() <= CommunityProfile::MAX_DESCRIPTION_LEN,
+            VotingSystemError::DescriptionTooLong
+        );
+        require!(
+            data.social_links.len() <= CommunityProfile::MAX_SOCIAL_LINKS,
+            VotingSystemError::TooManySocialLinks
+        );
         
-        // 4. Verificar membresía activa en la comunidad (ya validado por constraints)
-        // Los constraints ya verifican:
-        // - membership.community == vote.community
-        // - membership.user == voter.key()
-        // - membership.is_active
-        // - user.wallet == voter.key()
+        for uri in [&data.avatar_uri, &data.banner_uri, &data.website_uri, &data.rules_uri].into_iter().flatten() {
+            validate_uri(uri, CommunityProfile::MAX_URI_LEN)?;
+        }
+        for link in &data.social_links {
+            validate_uri(link, CommunityProfile::MAX_SOCIAL_LINK_LEN)?;
+        }
         
-        // 5. El usuario ya votó se previene automáticamente por PDA único en Participation
+        // Las normas deben poder verificarse contra su hash
+        if data.rules_uri.is_some() {
+            require!(data.rules_hash.is_some(), VotingSystemError::MissingRulesHash);
+        }
         
-        // === INICIALIZAR PARTICIPATION ACCOUNT ===
-        let participation = &mut ctx.accounts.participation;
+        let profile = &mut ctx.accounts.profile;
+        let clock = Clock::get()?;
         
-        participation.user = ctx.accounts.user.wallet;
-        participation.vote = vote.key();
-        participation.option_selected = option_selected;
-        participation.voted_at = clock.unix_timestamp;
-        participation.bump = ctx.bumps.participation;
+        profile.community = ctx.accounts.community.key();
+        profile.description = data.description;
+        profile.avatar_uri = data.avatar_uri;
+        profile.banner_uri = data.banner_uri;
+        profile.website_uri = data.website_uri;
+        profile.social_links = data.social_links;
+        profile.rules_uri = data.rules_uri;
+        profile.rules_hash = data.rules_hash;
+        profile.updated_by = ctx.accounts.admin.key();
+        profile.updated_at = clock.unix_timestamp;
+        profile.bump = ctx.bumps.profile;
+        
+        msg!("🖼️ Community profile updated!");
+        msg!("Community: {}", ctx.accounts.community.name);
+        msg!("Updated by: {}", profile.updated_by);
+        if let Some(rules_uri) = &profile.rules_uri {
+            msg!("Rules: {}", rules_uri);
+        }
         
-        // === ACTUALIZAR RESULTADOS DE VOTACIÓN ===
-        // Añadir el usuario a la lista de participantes
-        vote.participants.push(ctx.accounts.user.wallet);
+        Ok(())
+    }
+    
+    // Paso 1: la authority actual propone a un miembro activo como nueva authority
+    pub fn propose_authority_transfer(
+        ctx: Context<ProposeAuthorityTransfer>,
+    ) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let new_authority = ctx.accounts.new_authority_membership.user;
         
-        // TAREA 2.5.7: Sistema de voto ponderado
-        // TODO: Implementar weighted_voting_enabled field en Vote struct
-        // Por ahora usar voto estándar
-        vote.results[option_selected as usize] += 1;
+        require!(community.council.is_none(), VotingSystemError::CouncilApprovalRequired);
+        require!(new_authority != community.authority, VotingSystemError::AlreadyCommunityAuthority);
         
-        // Incrementar total de votos (siempre +1 para quorum)
-        vote.total_votes += 1;
+        // Proponer de nuevo sustituye la propuesta anterior
+        community.pending_authority = Some(new_authority);
         
-        // === ACTUALIZAR ESTADÍSTICAS DE USUARIO ===
-        let user_account = &mut ctx.accounts.user;
-        user_account.total_votes_cast += 1;
+        msg!("🔑 Authority transfer proposed!");
+        msg!("Community: {}", community.name);
+        msg!("Current authority: {}", community.authority);
+        msg!("Proposed authority: {}", new_authority);
         
-        // Sistema básico de reputación: +1 punto por votar
-        user_account.reputation_points += 1;
+        Ok(())
+    }
+    
+    // Paso 2: la authority propuesta acepta y pasa a ser Admin si no lo era
+    pub fn accept_authority_transfer(
+        ctx: Context<AcceptAuthorityTransfer>,
+    ) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let membership = &mut ctx.accounts.new_authority_membership;
+        let new_authority = ctx.accounts.new_authority.key();
         
-        // TAREA 2.5.6: Actualizar voting_weight automáticamente
-        user_account.update_voting_weight();
+        require!(
+            community.pending_authority == Some(new_authority),
+            VotingSystemError::NoPendingAuthorityTransfer
+        );
         
-        // Subir de nivel cada 10 puntos
-        let new_level = (user_account.reputation_points / 10) + 1;
-        if new_level as u32 > user_account.level {
-            user_account.level = new_level as u32;
-            msg!("🎉 User leveled up to level {}!", new_level);
+        if !membership.is_admin() {
+            require!(community.admin_count < Community::MAX_ADMINS, VotingSystemError::TooManyAdmins);
+            community.unlist_moderator(&membership.user);
+            membership.role = UserRole::Admin;
+            community.admin_count += 1;
         }
         
-        // === VERIFICAR QUORUM DINÁMICO Y CERRAR VOTACIÓN SI SE ALCANZA ===
-        // TODO: Obtener community data para quorum calculation
-        // Por ahora usar quorum absoluto desde vote
-        let required_quorum = vote.quorum_required;
+        let previous_authority = community.authority;
+        community.authority = new_authority;
+        community.pending_authority = None;
         
-        if vote.total_votes >= required_quorum {
-            vote.status = VoteStatus::Completed;
-            msg!("🎯 Quorum reached! Vote completed automatically.");
-            
-            // Para Knowledge type: verificar respuesta correcta y otorgar puntos extra
-            if vote.vote_type == VoteType::Knowledge {
-                if let Some(correct_answer) = vote.correct_answer {
-                    if option_selected == correct_answer {
-                        user_account.reputation_points += 3; // +3 puntos por respuesta correcta
-                        msg!("✅ Correct answer! +3 bonus reputation points.");
-                    }
-                }
-            }
-        }
+        msg!("✅ Authority transfer accepted!");
+        msg!("Community: {}", community.name);
+        msg!("Previous authority: {} (remains admin)", previous_authority);
+        msg!("New authority: {}", new_authority);
         
-        // === LOGS DETALLADOS PARA DEBUGGING ===
-        msg!("🗳️ Vote cast successfully!");
-        msg!("User: {}", user_account.wallet);
-        msg!("Vote: {}", vote.question);
-        msg!("Option selected: {} ({})", option_selected, vote.options[option_selected as usize]);
-        msg!("Total votes now: {}/{}", vote.total_votes, required_quorum);
-        msg!("User reputation: {} points, level: {}, weight: {}x", 
-             user_account.reputation_points, user_account.level, user_account.voting_weight);
-        msg!("Vote status: {:?}", vote.status);
+        Ok(())
+    }
+    
+    // La authority nombra co-admins entre los miembros activos
+    pub fn promote_admin(
+        ctx: Context<ManageAdmin>,
+    ) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let membership = &mut ctx.accounts.membership;
         
-        // TODO: Implementar weighted voting results display
-        msg!("🗺️ Standard voting - results by count:");
-        for (i, count) in vote.results.iter().enumerate() {
-            msg!("  Option {}: {} votes", i, count);
-        }
+        require!(membership.is_active, VotingSystemError::NotCommunityMember);
+        require!(membership.role != UserRole::Banned, VotingSystemError::UserBanned);
+        require!(!membership.is_admin(), VotingSystemError::AlreadyAdmin);
+        require!(community.admin_count < Community::MAX_ADMINS, VotingSystemError::TooManyAdmins);
         
-        if vote.use_percentage_quorum {
-            msg!("Quorum type: {}% percentage based", vote.quorum_percentage.unwrap_or(50));
-        } else {
-            msg!("Quorum type: {} absolute votes", vote.quorum_required);
-        }
+        community.unlist_moderator(&membership.user);
+        membership.role = UserRole::Admin;
+        community.admin_count += 1;
+        
+        msg!("⬆️ Admin promoted!");
+        msg!("User: {}", membership.user);
+        msg!("Community: {}", community.name);
+        msg!("Total admins: {}", community.admin_count);
         
         Ok(())
     }
-
-    pub fn request_membership(
-        ctx: Context<RequestMembership>,
-        message: String,
+    
+    pub fn demote_admin(
+        ctx: Context<ManageAdmin>,
     ) -> Result<()> {
-        require!(message.len() <= 300, VotingSystemError::RequestMessageTooLong);
-        
-        let request = &mut ctx.accounts.membership_request;
-        let community = &ctx.accounts.community;
-        let user = &ctx.accounts.user;
-        let clock = Clock::get()?;
+        let community = &mut ctx.accounts.community;
+        let membership = &mut ctx.accounts.membership;
         
-        // Validaciones
-        require!(community.is_active, VotingSystemError::CommunityInactive);
+        require!(membership.is_admin(), VotingSystemError::NotAdmin);
+        require!(membership.user != community.authority, VotingSystemError::CannotDemoteAuthority);
         
-        // Inicializar solicitud
-        request.user = ctx.accounts.requester.key();
-        request.community = community.key();
-        request.message = message.clone();
-        request.status = MembershipRequestStatus::Pending;
-        request.requested_at = clock.unix_timestamp;
-        request.reviewed_by = None;
-        request.reviewed_at = None;
-        request.admin_notes = String::new();
-        request.bump = ctx.bumps.membership_request;
+        membership.role = UserRole::Member;
+        community.admin_count = community.admin_count.saturating_sub(1);
         
-        msg!("🔔 Membership request submitted successfully!");
-        msg!("User: {}", ctx.accounts.requester.key());
+        msg!("⬇️ Admin demoted to member!");
+        msg!("User: {}", membership.user);
         msg!("Community: {}", community.name);
-        msg!("Message: {}", message);
-        msg!("Requested at: {}", clock.unix_timestamp);
+        msg!("Total admins: {}", community.admin_count);
         
         Ok(())
     }
     
-    pub fn approve_membership(
-        ctx: Context<ApproveMembership>,
-        admin_notes: String,
+    // === CONSEJO DE ADMINS (M-DE-N) ===
+    
+    pub fn create_admin_council<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateAdminCouncil<'info>>,
+        members: Vec<Pubkey>,
+        threshold: u8,
     ) -> Result<()> {
-        require!(admin_notes.len() <= 200, VotingSystemError::AdminNotesTooLong);
+        require!(
+            !members.is_empty() && members.len() <= AdminCouncil::MAX_MEMBERS,
+            VotingSystemError::InvalidCouncilMembers
+        );
+        require!(
+            (1..=members.len()).contains(&(threshold as usize)),
+            VotingSystemError::InvalidCouncilThreshold
+        );
         
-        let request = &mut ctx.accounts.membership_request;
-        let membership = &mut ctx.accounts.membership;
+        // remaining_accounts: membership Admin de cada miembro, en el mismo orden
+        require!(ctx.remaining_accounts.len() == members.len(), VotingSystemError::InvalidCouncilMembers);
+        let community_key = ctx.accounts.community.key();
+        for (i, member) in members.iter().enumerate() {
+            require!(!members[..i].contains(member), VotingSystemError::InvalidCouncilMembers);
+            
+            let membership = Account::<Membership>::try_from(&ctx.remaining_accounts[i])?;
+            require!(
+                membership.user == *member &&
+                membership.community == community_key &&
+                membership.is_active &&
+                membership.is_admin(),
+                VotingSystemError::InvalidCouncilMembers
+            );
+        }
+        
+        let council = &mut ctx.accounts.council;
         let community = &mut ctx.accounts.community;
-        let admin_membership = &ctx.accounts.admin_membership;
         let clock = Clock::get()?;
         
-        // Validaciones
-        require!(admin_membership.is_admin(), VotingSystemError::InsufficientPermissions);
-        require!(request.status == MembershipRequestStatus::Pending, VotingSystemError::RequestNotPending);
-        
-        // Actualizar solicitud
-        request.status = MembershipRequestStatus::Approved;
-        request.reviewed_by = Some(admin_membership.user);
-        request.reviewed_at = Some(clock.unix_timestamp);
-        request.admin_notes = admin_notes.clone();
-        
-        // Crear membership (lo que hacía join_community)
-        membership.user = request.user;
-        membership.community = community.key();
-        membership.role = UserRole::Member;
-        membership.joined_at = clock.unix_timestamp;
-        membership.is_active = true;
-        membership.bump = ctx.bumps.membership;
-        
-        // Actualizar stats de comunidad
-        community.total_members += 1;
+        council.community = community.key();
+        council.members = members.clone();
+        council.threshold = threshold;
+        council.action_nonce = 0;
+        council.created_at = clock.unix_timestamp;
+        council.bump = ctx.bumps.council;
         
-        // Crear log de moderación
-        let moderation_log = &mut ctx.accounts.moderation_log;
-        moderation_log.community = community.key();
-        moderation_log.moderator = admin_membership.user;
-        moderation_log.target_user = Some(request.user);
-        moderation_log.target_vote = None;
-        moderation_log.action = ModerationAction::ApproveMembership;
-        moderation_log.reason = admin_notes;
-        moderation_log.executed_at = clock.unix_timestamp;
-        moderation_log.bump = ctx.bumps.moderation_log;
+        // Desde ahora withdraw_fees, update_community y la transferencia de authority requieren el consejo;
+        // promote_admin y demote_admin quedan bloqueados (la composición solo cambia con ReplaceMember)
+        community.council = Some(council.key());
         
-        msg!("✅ Membership request approved successfully!");
-        msg!("User: {}", request.user);
+        msg!("🏛️ Admin council created!");
         msg!("Community: {}", community.name);
-        msg!("Approved by: {}", admin_membership.user);
-        msg!("Total members now: {}", community.total_members);
+        msg!("Threshold: {} of {}", threshold, members.len());
+        for member in &members {
+            msg!("  Member: {}", member);
+        }
         
         Ok(())
     }
     
-    pub fn reject_membership(
-        ctx: Context<RejectMembership>,
-        admin_notes: String,
+    pub fn propose_council_action(
+        ctx: Context<ProposeCouncilAction>,
+        action: CouncilAction,
     ) -> Result<()> {
-        require!(admin_notes.len() <= 200, VotingSystemError::AdminNotesTooLong);
-        
-        let request = &mut ctx.accounts.membership_request;
-        let admin_membership = &ctx.accounts.admin_membership;
+        let council = &mut ctx.accounts.council;
+        let pending_action = &mut ctx.accounts.pending_action;
+        let proposer = ctx.accounts.proposer.key();
         let clock = Clock::get()?;
         
-        // Validaciones
-        require!(admin_membership.is_admin(), VotingSystemError::InsufficientPermissions);
-        require!(request.status == MembershipRequestStatus::Pending, VotingSystemError::RequestNotPending);
+        require!(council.is_member(&proposer), VotingSystemError::NotCouncilMember);
         
-        // Actualizar solicitud
-        request.status = MembershipRequestStatus::Rejected;
-        request.reviewed_by = Some(admin_membership.user);
-        request.reviewed_at = Some(clock.unix_timestamp);
-        request.admin_notes = admin_notes.clone();
+        pending_action.council = council.key();
+        pending_action.community = council.community;
+        pending_action.proposer = proposer;
+        pending_action.action = action.clone();
+        pending_action.approvals = vec![proposer]; // Proponer cuenta como aprobación
+        pending_action.nonce = council.action_nonce;
+        pending_action.created_at = clock.unix_timestamp;
+        pending_action.expires_at = clock.unix_timestamp + PendingAction::TTL;
+        pending_action.bump = ctx.bumps.pending_action;
         
-        // Crear log de moderación
-        let moderation_log = &mut ctx.accounts.moderation_log;
-        moderation_log.community = request.community;
-        moderation_log.moderator = admin_membership.user;
-        moderation_log.target_user = Some(request.user);
-        moderation_log.target_vote = None;
-        moderation_log.action = ModerationAction::RejectMembership;
-        moderation_log.reason = admin_notes;
-        moderation_log.executed_at = clock.unix_timestamp;
-        moderation_log.bump = ctx.bumps.moderation_log;
+        council.action_nonce += 1;
         
-        msg!("❌ Membership request rejected!");
-        msg!("User: {}", request.user);
-        msg!("Community: {}", request.community);
-        msg!("Rejected by: {}", admin_membership.user);
-        msg!("Reason: {}", request.admin_notes);
+        msg!("📜 Council action proposed!");
+        msg!("Action: {:?}", action);
+        msg!("Proposer: {}", proposer);
+        msg!("Approvals: {}/{}", pending_action.approvals.len(), council.threshold);
+        msg!("Expires at: {}", pending_action.expires_at);
         
         Ok(())
     }
-
-    pub fn join_community(ctx: Context<JoinCommunity>) -> Result<()> {
-        // === VALIDACIONES ===
-        require!(ctx.accounts.community.is_active, VotingSystemError::CommunityInactive);
-        
-        // Verificar si la comunidad requiere aprobación
-        require!(!ctx.accounts.community.requires_approval, VotingSystemError::CommunityRequiresApproval);
-        
-        // Verificar que el usuario no sea ya miembro (PDA existe = ya es miembro)
-        // Esta validación se hace automáticamente por Anchor al intentar crear PDA existente
-        
-        // === INICIALIZAR MEMBERSHIP ACCOUNT ===
-        let membership = &mut ctx.accounts.membership;
-        let community = &mut ctx.accounts.community;
-        let user = &ctx.accounts.user;
+    
+    pub fn approve_council_action(
+        ctx: Context<ApproveCouncilAction>,
+    ) -> Result<()> {
+        let council = &ctx.accounts.council;
+        let pending_action = &mut ctx.accounts.pending_action;
+        let member = ctx.accounts.member.key();
         let clock = Clock::get()?;
         
-        membership.user = ctx.accounts.member.key(); // Usar member.key() (wallet) no user.key() (PDA)
-        membership.community = community.key();
-        membership.role = UserRole::Member; // Rol por defecto
-        membership.joined_at = clock.unix_timestamp;
-        membership.is_active = true;
-        membership.bump = ctx.bumps.membership;
+        require!(council.is_member(&member), VotingSystemError::NotCouncilMember);
+        require!(!pending_action.is_expired(clock.unix_timestamp), VotingSystemError::CouncilActionExpired);
+        require!(!pending_action.has_approved(&member), VotingSystemError::AlreadyApproved);
         
-        // === ACTUALIZAR ESTADÍSTICAS DE COMUNIDAD ===
-        community.total_members += 1;
+        pending_action.approvals.push(member);
         
-        // === LOGS PARA DEBUGGING ===
-        msg!("✅ User joined community successfully!");
-        msg!("User: {}", user.key());
-        msg!("Community: {}", community.name);
-        msg!("Total members now: {}", community.total_members);
-        msg!("Joined at: {}", clock.unix_timestamp);
+        msg!("✍️ Council action approved!");
+        msg!("Member: {}", member);
+        msg!("Approvals: {}/{}", pending_action.approvals.len(), council.threshold);
         
         Ok(())
     }
-
-    pub fn create_knowledge_voting(
-        ctx: Context<CreateKnowledgeVoting>,
-        question: String,
-        options: Vec<String>,
-        vote_type: VoteType,
-        correct_answer: Option<u8>,
-        deadline_hours: u32,
-        quorum_required: u64,
+    
+    // Ejecutar la acción una vez alcanzado el umbral; el account se cierra al proposer
+    pub fn execute_council_action(
+        ctx: Context<ExecuteCouncilAction>,
     ) -> Result<()> {
-        // Reutilizar la misma lógica que create_voting
-        // === VALIDACIONES BÁSICAS ===
-        require!(question.len() > 0 && question.len() <= 200, VotingSystemError::QuestionTooLong);
-        require!(options.len() >= 2 && options.len() <= 4, VotingSystemError::InvalidOptionsCount);
-        require!(deadline_hours >= 1 && deadline_hours <= 168, VotingSystemError::InvalidDeadline);
-        require!(quorum_required > 0, VotingSystemError::InvalidQuorum);
+        let council = &ctx.accounts.council;
+        let pending_action = &ctx.accounts.pending_action;
+        let community = &mut ctx.accounts.community;
+        let clock = Clock::get()?;
         
-        for option in &options {
-            require!(option.len() > 0 && option.len() <= 50, VotingSystemError::OptionTooLong);
+        require!(council.is_member(&ctx.accounts.executor.key()), VotingSystemError::NotCouncilMember);
+        require!(!pending_action.is_expired(clock.unix_timestamp), VotingSystemError::CouncilActionExpired);
+        require!(
+            pending_action.valid_approvals(council) >= council.threshold as usize,
+            VotingSystemError::ThresholdNotReached
+        );
+        let approvals = pending_action.valid_approvals(council);
+        
+        match &pending_action.action {
+            CouncilAction::WithdrawFees { amount, recipient } => {
+                let recipient_info = ctx.accounts.recipient.as_ref().ok_or(VotingSystemError::InvalidRecipient)?;
+                require!(recipient_info.key() == *recipient, VotingSystemError::InvalidRecipient);
+                require!(
+                    vault_available_balance(&ctx.accounts.vault)? >= *amount,
+                    VotingSystemError::InsufficientFunds
+                );
+                
+                transfer_from_vault(
+                    &ctx.accounts.vault,
+                    &recipient_info.to_account_info(),
+                    &ctx.accounts.system_program,
+                    community.key(),
+                    ctx.bumps.vault,
+                    *amount,
+                )?;
+                
+                msg!("💰 Fees withdrawn: {} lamports to {}", amount, recipient);
+                msg!("Remaining fees: {} lamports", vault_available_balance(&ctx.accounts.vault)?);
+            }
+            CouncilAction::UpdateCommunity { settings } => {
+                apply_community_settings(community, settings)?;
+            }
+            CouncilAction::TransferAuthority { new_authority } => {
+                require!(*new_authority != community.authority, VotingSystemError::AlreadyCommunityAuthority);
+                
+                // Igual que propose_authority_transfer: la nueva authority debe aceptar
+                community.pending_authority = Some(*new_authority);
+                msg!("🔑 Authority transfer proposed to {}", new_authority);
+            }
+            CouncilAction::Archive => {
+                require!(!community.is_archived(), VotingSystemError::CommunityArchived);
+                
+                // Los fees pendientes se liquidan a la authority, igual que en archive_community
+                let recipient_info = ctx.accounts.recipient.as_ref().ok_or(VotingSystemError::InvalidRecipient)?;
+                require!(recipient_info.key() == community.authority, VotingSystemError::InvalidRecipient);
+                
+                settle_and_archive(
+                    community,
+                    &ctx.accounts.vault,
+                    &recipient_info.to_account_info(),
+                    &ctx.accounts.system_program,
+                    ctx.bumps.vault,
+                )?;
+            }
+            CouncilAction::ReplaceMember { old_member, new_member } => {
+                let council = &mut ctx.accounts.council;
+                require!(council.is_member(old_member), VotingSystemError::NotCouncilMember);
+                require!(!council.is_member(new_member), VotingSystemError::InvalidCouncilMembers);
+                
+                // El nuevo miembro debe ser admin activo de la comunidad en el momento de ejecutar
+                let membership = ctx.accounts.new_member_membership.as_ref().ok_or(VotingSystemError::InvalidCouncilMembers)?;
+                require!(
+                    membership.user == *new_member &&
+                    membership.community == community.key() &&
+                    membership.is_active &&
+                    membership.is_admin(),
+                    VotingSystemError::InvalidCouncilMembers
+                );
+                
+                if let Some(slot) = council.members.iter_mut().find(|member| *member == old_member) {
+                    *slot = *new_member;
+                }
+                
+                msg!("🔄 Council member replaced: {} -> {}", old_member, new_member);
+            }
         }
         
-        // Validaciones específicas para Knowledge type
-        if vote_type == VoteType::Knowledge {
-            require!(correct_answer.is_some(), VotingSystemError::MissingCorrectAnswer);
-            let answer = correct_answer.unwrap();
-            require!((answer as usize) < options.len(), VotingSystemError::InvalidCorrectAnswer);
-        }
+        msg!("✅ Council action executed!");
+        msg!("Approvals: {}/{}", approvals, ctx.accounts.council.threshold);
         
-        // === SISTEMA DE FEES (0.01 SOL) ===
-        const VOTING_FEE: u64 = 10_000_000;
+        Ok(())
+    }
+    
+    // Sacar del consejo a quien ya no es admin activo (membership cerrada o inactiva).
+    // Sin firma de admin: solo limpia miembros muertos, nunca rebaja el umbral
+    pub fn remove_stale_council_member(
+        ctx: Context<RemoveStaleCouncilMember>,
+        member: Pubkey,
+    ) -> Result<()> {
+        let council = &mut ctx.accounts.council;
+        let membership_info = &ctx.accounts.member_membership;
         
-        let fee_transfer = anchor_lang::system_program::Transfer {
-            from: ctx.accounts.creator.to_account_info(),
-            to: ctx.accounts.community.to_account_info(),
+        require!(council.is_member(&member), VotingSystemError::NotCouncilMember);
+        
+        let still_admin = !membership_info.data_is_empty() && {
+            let membership = Membership::try_deserialize(&mut &membership_info.try_borrow_data()?[..])?;
+            membership.is_active && membership.is_admin()
         };
+        require!(!still_admin, VotingSystemError::CouncilMemberStillActive);
         
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            fee_transfer,
-        );
+        // El umbral no se rebaja: si no quedan miembros suficientes, hay que rotar con ReplaceMember
+        require!(council.can_remove_member(), VotingSystemError::InvalidCouncilThreshold);
+        council.remove_member(&member);
         
-        anchor_lang::system_program::transfer(cpi_context, VOTING_FEE)?;
+        msg!("🧹 Stale council member removed!");
+        msg!("Member: {}", member);
+        msg!("Threshold: {} of {}", council.threshold, council.members.len());
         
-        // === INICIALIZACIÓN DE VOTE ACCOUNT ===
-        let vote = &mut ctx.accounts.vote;
-        let community = &mut ctx.accounts.community;
+        Ok(())
+    }
+    
+    // Limpiar acciones caducadas sin ejecutar (rent al proposer)
+    pub fn close_expired_council_action(
+        ctx: Context<CloseExpiredCouncilAction>,
+    ) -> Result<()> {
+        let pending_action = &ctx.accounts.pending_action;
         let clock = Clock::get()?;
         
-        vote.community = community.key();
-        vote.creator = ctx.accounts.creator.key();
-        vote.question = question.clone();
-        vote.vote_type = vote_type;
-        vote.options = options.clone();
-        vote.correct_answer = correct_answer;
+        require!(pending_action.is_expired(clock.unix_timestamp), VotingSystemError::CouncilActionNotExpired);
+        
+        msg!("🗑️ Expired council action closed");
+        msg!("Action: {:?}", pending_action.action);
+        msg!("Approvals reached: {}", pending_action.approvals.len());
+        
+        Ok(())
+    }
+    
+    // === ARCHIVO Y CIERRE DE COMUNIDADES ===
+    
+    // Congela la comunidad (sin votaciones ni altas nuevas) y liquida los fees pendientes
+    pub fn archive_community(
+        ctx: Context<ArchiveCommunity>,
+    ) -> Result<()> {
+        // Con consejo, el archivo pasa por CouncilAction::Archive
+        require!(ctx.accounts.community.council.is_none(), VotingSystemError::CouncilApprovalRequired);
+        
+        settle_and_archive(
+            &mut ctx.accounts.community,
+            &ctx.accounts.vault,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.bumps.vault,
+        )
+    }
+    
+    // Cerrar una membership de una comunidad archivada (rent al miembro)
+    pub fn close_membership(
+        ctx: Context<CloseMembership>,
+    ) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let membership = &ctx.accounts.membership;
+        
+        if membership.is_active {
+            community.total_members = community.total_members.saturating_sub(1);
+        }
+        if membership.is_admin() {
+            community.admin_count = community.admin_count.saturating_sub(1);
+        }
+        community.unlist_moderator(&membership.user);
+        
+        msg!("🗑️ Membership closed");
+        msg!("User: {}", membership.user);
+        msg!("Community: {}", community.name);
+        msg!("Remaining members: {}", community.total_members);
+        
+        Ok(())
+    }
+    
+    pub fn close_community_leaderboard(
+        ctx: Context<CloseCommunityLeaderboard>,
+    ) -> Result<()> {
+        msg!("🗑️ Community leaderboard closed");
+        msg!("Community: {}", ctx.accounts.community.name);
+        
+        Ok(())
+    }
+    
+    pub fn close_community_profile(
+        ctx: Context<CloseCommunityProfile>,
+    ) -> Result<()> {
+        msg!("🗑️ Community profile closed");
+        msg!("Community: {}", ctx.accounts.community.name);
+        
+        Ok(())
+    }
+    
+    pub fn close_custom_category(
+        ctx: Context<CloseCustomCategory>,
+    ) -> Result<()> {
+        msg!("🗑️ Custom category closed");
+        msg!("Category: {}", ctx.accounts.custom_category.name);
+        msg!("Community: {}", ctx.accounts.community.name);
+        
+        Ok(())
+    }
+    
+    // Último paso: cerrar la comunidad cuando ya no quedan memberships
+    pub fn close_community(
+        ctx: Context<CloseCommunity>,
+    ) -> Result<()> {
+        let community = &ctx.accounts.community;
+        
+        require!(community.total_members == 0, VotingSystemError::CommunityHasMembers);
+        require!(community.child_count == 0, VotingSystemError::CommunityHasChildren);
+        
+        // Una sub-comunidad libera su plaza en la padre
+        if let Some(parent_key) = community.parent {
+            let parent = ctx.accounts.parent.as_mut().ok_or(VotingSystemError::InvalidParentCommunity)?;
+            require!(parent.key() == parent_key, VotingSystemError::InvalidParentCommunity);
+            parent.child_count = parent.child_count.saturating_sub(1);
+        }
+        
+        msg!("🗑️ Community closed");
+        msg!("Community: {}", community.name);
+        msg!("Rent returned to: {}", community.authority);
+        
+        Ok(())
+    }
+
+    // Crear un rol con nombre y permisos propios de la comunidad
+    pub fn create_community_role(
+        ctx: Context<CreateCommunityRole>,
+        name: String,
+        permissions: u16,
+    ) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= CommunityRole::MAX_NAME_LEN,
+            VotingSystemError::InvalidRoleName
+        );
+        require!(CommunityRole::is_valid_permissions(permissions), VotingSystemError::InvalidPermissions);
+        
+        let role = &mut ctx.accounts.role;
+        let clock = Clock::get()?;
+        
+        role.community = ctx.accounts.community.key();
+        role.name = name;
+        role.permissions = permissions;
+        role.created_by = ctx.accounts.admin.key();
+        role.created_at = clock.unix_timestamp;
+        role.updated_at = clock.unix_timestamp;
+        role.bump = ctx.bumps.role;
+        
+        msg!("🏷️ Community role created!");
+        msg!("Role: {}", role.name);
+        msg!("Community: {}", ctx.accounts.community.name);
+        msg!("Permissions: {:#06b}", role.permissions);
+        
+        Ok(())
+    }
+    
+    // Cambiar los permisos de un rol (afecta a todos los que lo tienen)
+    pub fn update_community_role(
+        ctx: Context<UpdateCommunityRole>,
+        permissions: u16,
+    ) -> Result<()> {
+        require!(CommunityRole::is_valid_permissions(permissions), VotingSystemError::InvalidPermissions);
+        
+        let role = &mut ctx.accounts.role;
+        
+        msg!("🏷️ Community role updated");
+        msg!("Role: {}", role.name);
+        msg!("Permissions: {:#06b} → {:#06b}", role.permissions, permissions);
+        
+        role.permissions = permissions;
+        role.updated_at = Clock::get()?.unix_timestamp;
+        
+        Ok(())
+    }
+    
+    // Borrar un rol; quien lo tuviera asignado lo pierde en su siguiente uso (rent al admin)
+    pub fn delete_community_role(ctx: Context<DeleteCommunityRole>) -> Result<()> {
+        let role = &ctx.accounts.role;
+        
+        msg!("🗑️ Community role deleted");
+        msg!("Role: {}", role.name);
+        msg!("Community: {}", ctx.accounts.community.name);
+        msg!("Deleted by: {}", ctx.accounts.admin.key());
+        
+        Ok(())
+    }
+    
+    // Asignar un rol a un miembro (None = quitar el rol actual)
+    pub fn assign_community_role(ctx: Context<AssignCommunityRole>) -> Result<()> {
+        let membership = &mut ctx.accounts.membership;
+        
+        membership.custom_role = ctx.accounts.role.as_ref().map(|role| role.key());
+        membership.custom_role_assigned_at = Clock::get()?.unix_timestamp;
+        
+        msg!("🏷️ Community role assigned");
+        msg!("User: {}", membership.user);
+        match &ctx.accounts.role {
+            Some(role) => msg!("Role: {}", role.name),
+            None => msg!("Role: none"),
+        }
+        msg!("Assigned by: {}", ctx.accounts.admin.key());
+        
+        Ok(())
+    }
+    
+    pub fn create_voting(
+        ctx: Context<CreateVoting>,
+        question: String,
+        options: Vec<String>,
+        vote_type: VoteType,
+        answer_hash: Option<[u8; 32]>,
+        deadline_hours: u32,
+        settings: VoteSettings,
+    ) -> Result<()> {
+        let VoteSettings {
+            quorum_required,
+            quorum_percentage,
+            use_percentage_quorum,
+            weighted_voting_enabled,
+            speed_bonus,
+            sealed_results,
+        } = settings;
+        
+        // === VALIDACIONES BÁSICAS ===
+        require!(question.len() > 0 && question.len() <= 200, VotingSystemError::QuestionTooLong);
+        require!(options.len() >= 2 && options.len() <= 4, VotingSystemError::InvalidOptionsCount);
+        require!(deadline_hours >= 1 && deadline_hours <= 168, VotingSystemError::InvalidDeadline); // 1 hora a 1 semana
+        
+        // Validaciones de quorum
+        if use_percentage_quorum {
+            require!(quorum_percentage.is_some(), VotingSystemError::MissingQuorumPercentage);
+            let percentage = quorum_percentage.unwrap();
+            require!(percentage > 0 && percentage <= 100, VotingSystemError::InvalidQuorumPercentage);
+        } else {
+            require!(quorum_required > 0, VotingSystemError::InvalidQuorum);
+        }
+        
+        // Validar longitud de cada opción
+        for option in &options {
+            require!(option.len() > 0 && option.len() <= 50, VotingSystemError::OptionTooLong);
+        }
+        
+        // Validaciones específicas para Knowledge type
+        // La respuesta correcta se compromete como hash y se revela tras el deadline
+        if vote_type == VoteType::Knowledge {
+            require!(answer_hash.is_some(), VotingSystemError::MissingAnswerHash);
+        }
+        
+        // El creador debe ser miembro con las cuotas al día
+        require!(
+            ctx.accounts.membership.is_in_good_standing(Clock::get()?.unix_timestamp),
+            VotingSystemError::MembershipExpired
+        );
+        require!(
+            has_permission(
+                &mut ctx.accounts.membership,
+                &ctx.accounts.custom_role,
+                ctx.accounts.community.member_permissions,
+                PERMISSION_CREATE_VOTE,
+            )?,
+            VotingSystemError::InsufficientPermissions
+        );
+        
+        // El bonus por rapidez solo tiene sentido con respuesta correcta
+        if let Some(bonus) = speed_bonus {
+            require!(
+                vote_type == VoteType::Knowledge && bonus.is_valid(),
+                VotingSystemError::InvalidSpeedBonus
+            );
+        }
+        
+        // === SISTEMA DE FEES DINÁMICO ===
+        // Calcular fee basado en la reputación del usuario
+        let user = &ctx.accounts.user;
+        let fee_tier = FeeTier::from_reputation(user.reputation_points);
+        let voting_fee = fee_tier.get_voting_fee();
+        
+        // Solo transferir si el fee es mayor a 0
+        if voting_fee > 0 {
+            // Transferir fee del creator al vault de la comunidad
+            let fee_transfer = anchor_lang::system_program::Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            };
+            
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                fee_transfer,
+            );
+            
+            anchor_lang::system_program::transfer(cpi_context, voting_fee)?;
+            
+            // Actualizar fee pool si existe
+            if let Some(fee_pool) = &mut ctx.accounts.fee_pool {
+                fee_pool.total_collected += voting_fee;
+            }
+        }
+        
+        // Knowledge: el creator respalda su respuesta con una fianza
+        let answer_bond = if vote_type == VoteType::Knowledge {
+            escrow_answer_bond(&ctx.accounts.creator, &ctx.accounts.vote, &ctx.accounts.system_program)?
+        } else {
+            0
+        };
+        
+        // === INICIALIZACIÓN DE VOTE ACCOUNT ===
+        let vote = &mut ctx.accounts.vote;
+        let community = &mut ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        vote.community = community.key();
+        vote.creator = ctx.accounts.creator.key();
+        vote.question = question.clone();
+        vote.vote_type = vote_type;
+        vote.options = options.clone();
+        vote.correct_answer = None; // Knowledge: se fija en reveal_correct_answer
         vote.participants = Vec::new();
         vote.results = vec![0; options.len()];
         vote.total_votes = 0;
         vote.quorum_required = quorum_required;
+        vote.quorum_percentage = quorum_percentage;
+        vote.use_percentage_quorum = use_percentage_quorum;
         vote.deadline = clock.unix_timestamp + (deadline_hours as i64 * 3600);
         vote.status = VoteStatus::Active;
-        vote.fee_per_vote = VOTING_FEE;
+        vote.fee_per_vote = voting_fee;
         vote.created_at = clock.unix_timestamp;
+        
+        // Votos sellados: siempre en Knowledge (los resultados en vivo delatan la respuesta), opcional en Opinion
+        vote.sealed_results = vote_type == VoteType::Knowledge || sealed_results;
+        vote.revealed_ballots = 0;
+        vote.answer_validated = false;
+        vote.answer_bond = answer_bond;
+        
+        // Commit-reveal: respuesta correcta (Knowledge) y votos sellados se revelan tras el deadline
+        vote.answer_hash = if vote_type == VoteType::Knowledge { answer_hash } else { None };
+        vote.reveal_deadline = if vote.sealed_results {
+            Some(vote.deadline + Vote::REVEAL_PERIOD)
+        } else {
+            None
+        };
+        vote.revealed_answer = None;
+        vote.explanation_hash = None;
+        vote.explanation_uri = None;
+        vote.federated_members = None;
+        vote.confidence_votes_for = 0;
+        vote.confidence_votes_against = 0;
+        vote.confidence_weight_for = 0.0;
+        vote.confidence_weight_against = 0.0;
+        vote.confidence_deadline = None;
+        
+        // Ponderación por voting_weight: solo para los votos de confianza
+        vote.weighted_voting_enabled = weighted_voting_enabled;
+        vote.weighted_results = Vec::new();
+        vote.speed_bonus = speed_bonus;
         vote.bump = ctx.bumps.vote;
         
         // === ACTUALIZAR ESTADÍSTICAS DE COMUNIDAD ===
         community.total_votes += 1;
-        community.fee_collected += VOTING_FEE;
         
-        msg!("✅ Knowledge Voting created successfully!");
+        // === LOGS PARA DEBUGGING ===
+        msg!("✅ Voting created successfully!");
         msg!("Question: {}", question);
         msg!("Options: {:?}", options);
         msg!("Type: {:?}", vote_type);
+        msg!("Creator: {}", ctx.accounts.creator.key());
+        msg!("Community: {}", community.name);
+        msg!("Fee collected: {} lamports (Tier: {:?})", voting_fee, fee_tier);
+        msg!("Deadline: {} hours from now", deadline_hours);
+        
+        if use_percentage_quorum {
+            msg!("Quorum: {}% of members", quorum_percentage.unwrap());
+        } else {
+            msg!("Quorum: {} absolute votes", quorum_required);
+        }
+        
+        if let Some(bonus) = speed_bonus {
+            msg!("Speed bonus: {} → {} points", bonus.max_bonus, bonus.min_bonus);
+        }
+        
+        if vote.sealed_results {
+            msg!("🔒 Sealed ballots: results hidden until reveal");
+        }
         
         Ok(())
     }
-}
-
-// ============================================================================
-// FUNCIONES AUXILIARES
-// ============================================================================
 
-// Calcular reward basado en reputación del usuario
-fn calculate_user_reward(reputation_points: u64, total_distribution: u64) -> u64 {
-    // Solo usuarios con 100+ puntos de reputación pueden reclamar rewards
-    if reputation_points < 100 {
-        return 0;
-    }
+    pub fn cast_vote(
+        ctx: Context<CastVote>,
+        option_selected: u8,
+    ) -> Result<()> {
+        // === VALIDACIONES CRÍTICAS ===
+        let vote = &mut ctx.accounts.vote;
+        let clock = Clock::get()?;
+        
+        // 0. Firmante: la wallet del usuario o una session key autorizada por ella
+        authorize_voter(
+            ctx.accounts.user.wallet,
+            ctx.accounts.voter.key(),
+            &mut ctx.accounts.session,
+            SESSION_SCOPE_CAST_VOTE,
+            clock.unix_timestamp,
+        )?;
+        
+        // Membresía con cuotas al día
+        require!(
+            ctx.accounts.membership.is_in_good_standing(clock.unix_timestamp),
+            VotingSystemError::MembershipExpired
+        );
+        
+        // 1. Verificar que la votación esté activa
+        require!(vote.status == VoteStatus::Active, VotingSystemError::VoteNotActive);
+        
+        // 2. Verificar deadline
+        require!(clock.unix_timestamp < vote.deadline, VotingSystemError::VoteExpired);
+        
+        // Los votos sellados van por commit_vote / reveal_ballot
+        require!(!vote.sealed_results, VotingSystemError::SealedVoteRequiresCommit);
+        
+        // 3. Verificar opción válida
+        require!((option_selected as usize) < vote.options.len(), VotingSystemError::InvalidOption);
+        
+        // 4. Verificar membresía activa en la comunidad (ya validado por constraints)
+        // Los constraints ya verifican:
+        // - membership.community == vote.community
+        // - membership.user == user.wallet
+        // - membership.is_active
+        // - session (si existe) pertenece a user.wallet y a la comunidad del vote
+        
+        // 5. El usuario ya votó se previene automáticamente por PDA único en Participation
+        
+        // === INICIALIZAR PARTICIPATION ACCOUNT ===
+        let participation = &mut ctx.accounts.participation;
+        
+        participation.user = ctx.accounts.user.wallet;
+        participation.vote = vote.key();
+        participation.option_selected = option_selected;
+        participation.voted_at = clock.unix_timestamp;
+        participation.reward_claimed = false;
+        participation.ballot_commitment = None;
+        participation.is_revealed = true;
+        participation.bump = ctx.bumps.participation;
+        
+        // === ACTUALIZAR RESULTADOS DE VOTACIÓN ===
+        // Añadir el usuario a la lista de participantes
+        vote.participants.push(ctx.accounts.user.wallet);
+        
+        // TAREA 2.5.7: Sistema de voto ponderado
+        // TODO: Implementar weighted_voting_enabled field en Vote struct
+        // Por ahora usar voto estándar
+        vote.results[option_selected as usize] += 1;
+        
+        // Incrementar total de votos (siempre +1 para quorum)
+        vote.total_votes += 1;
+        
+        // === ACTUALIZAR ESTADÍSTICAS DE USUARIO ===
+        let user_account = &mut ctx.accounts.user;
+        user_account.total_votes_cast += 1;
+        
+        // Sistema básico de reputación: +1 punto por votar
+        user_account.reputation_points += 1;
+        
+        // TAREA 2.5.6: Actualizar voting_weight automáticamente
+        user_account.update_voting_weight();
+        
+        // Subir de nivel cada 10 puntos
+        let new_level = (user_account.reputation_points / 10) + 1;
+        if new_level as u32 > user_account.level {
+            user_account.level = new_level as u32;
+            msg!("🎉 User leveled up to level {}!", new_level);
+        }
+        
+        // === VERIFICAR QUORUM DINÁMICO Y CERRAR VOTACIÓN SI SE ALCANZA ===
+        // TODO: Obtener community data para quorum calculation
+        // Por ahora usar quorum absoluto desde vote; los federados usan el snapshot de la federación
+        let required_quorum = match vote.federated_members {
+            Some(federated_members) => vote.calculate_required_quorum(federated_members),
+            None => vote.quorum_required,
+        };
+        
+        // Knowledge: sigue abierta hasta el deadline y pasa a AwaitingReveal
+        // (la respuesta correcta aún no se conoce, no hay bonus en este punto)
+        if vote.total_votes >= required_quorum && vote.vote_type == VoteType::Opinion {
+            vote.status = VoteStatus::Completed;
+            msg!("🎯 Quorum reached! Vote completed automatically.");
+        }
+        
+        // === LOGS DETALLADOS PARA DEBUGGING ===
+        msg!("🗳️ Vote cast successfully!");
+        msg!("User: {}", user_account.wallet);
+        msg!("Vote: {}", vote.question);
+        msg!("Option selected: {} ({})", option_selected, vote.options[option_selected as usize]);
+        msg!("Total votes now: {}/{}", vote.total_votes, required_quorum);
+        msg!("User reputation: {} points, level: {}, weight: {}x", 
+             user_account.reputation_points, user_account.level, user_account.voting_weight);
+        msg!("Vote status: {:?}", vote.status);
+        
+        // TODO: Implementar weighted voting results display
+        msg!("🗺️ Standard voting - results by count:");
+        for (i, count) in vote.results.iter().enumerate() {
+            msg!("  Option {}: {} votes", i, count);
+        }
+        
+        if vote.use_percentage_quorum {
+            msg!("Quorum type: {}% percentage based", vote.quorum_percentage.unwrap_or(50));
+        } else {
+            msg!("Quorum type: {} absolute votes", vote.quorum_required);
+        }
+        
+        Ok(())
+    }
+    
+    // Voto sellado: solo se guarda hash(option || salt || wallet) hasta el deadline
+    pub fn commit_vote(
+        ctx: Context<CommitVote>,
+        ballot_commitment: [u8; 32],
+    ) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let clock = Clock::get()?;
+        
+        authorize_voter(
+            ctx.accounts.user.wallet,
+            ctx.accounts.voter.key(),
+            &mut ctx.accounts.session,
+            SESSION_SCOPE_CAST_VOTE,
+            clock.unix_timestamp,
+        )?;
+        
+        require!(
+            ctx.accounts.membership.is_in_good_standing(clock.unix_timestamp),
+            VotingSystemError::MembershipExpired
+        );
+        require!(vote.status == VoteStatus::Active, VotingSystemError::VoteNotActive);
+        require!(clock.unix_timestamp < vote.deadline, VotingSystemError::VoteExpired);
+        require!(vote.sealed_results, VotingSystemError::VoteNotSealed);
+        
+        // La opción queda oculta: results no se toca hasta reveal_ballot
+        let participation = &mut ctx.accounts.participation;
+        
+        participation.user = ctx.accounts.user.wallet;
+        participation.vote = vote.key();
+        participation.option_selected = 0;
+        participation.voted_at = clock.unix_timestamp;
+        participation.reward_claimed = false;
+        participation.ballot_commitment = Some(ballot_commitment);
+        participation.is_revealed = false;
+        participation.bump = ctx.bumps.participation;
+        
+        vote.participants.push(ctx.accounts.user.wallet);
+        
+        // Cuenta para el quorum aunque la opción siga oculta
+        vote.total_votes += 1;
+        
+        let user_account = &mut ctx.accounts.user;
+        user_account.total_votes_cast += 1;
+        user_account.reputation_points += 1;
+        user_account.update_voting_weight();
+        
+        let new_level = (user_account.reputation_points / 10) + 1;
+        if new_level as u32 > user_account.level {
+            user_account.level = new_level as u32;
+            msg!("🎉 User leveled up to level {}!", new_level);
+        }
+        
+        msg!("🔒 Sealed vote committed!");
+        msg!("User: {}", user_account.wallet);
+        msg!("Vote: {}", vote.question);
+        msg!("Total votes now: {}/{}", vote.total_votes, vote.quorum_required);
+        
+        Ok(())
+    }
+    
+    // Revelar un voto sellado tras el deadline; cualquiera con el salt puede enviarlo
+    pub fn reveal_ballot(
+        ctx: Context<RevealBallot>,
+        option_selected: u8,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let participation = &mut ctx.accounts.participation;
+        let clock = Clock::get()?;
+        
+        require!(vote.is_ballot_reveal_open(clock.unix_timestamp), VotingSystemError::BallotRevealClosed);
+        require!(
+            !matches!(vote.status, VoteStatus::Cancelled | VoteStatus::Failed),
+            VotingSystemError::VoteNotActive
+        );
+        require!(!participation.is_revealed, VotingSystemError::BallotAlreadyRevealed);
+        require!((option_selected as usize) < vote.options.len(), VotingSystemError::InvalidOption);
+        
+        let commitment = participation.ballot_commitment.ok_or(VotingSystemError::VoteNotSealed)?;
+        let expected = Participation::compute_ballot_commitment(option_selected, &salt, &participation.user);
+        require!(commitment == expected, VotingSystemError::InvalidBallotReveal);
+        
+        participation.option_selected = option_selected;
+        participation.is_revealed = true;
+        
+        // Solo ahora se suma a los resultados
+        vote.results[option_selected as usize] += 1;
+        vote.revealed_ballots += 1;
+        
+        msg!("🔓 Ballot revealed!");
+        msg!("User: {}", participation.user);
+        msg!("Vote: {}", vote.question);
+        msg!("Option selected: {} ({})", option_selected, vote.options[option_selected as usize]);
+        msg!("Revealed ballots: {}/{}", vote.revealed_ballots, vote.total_votes);
+        
+        Ok(())
+    }
+    
+    // Cerrar una votación Opinion sellada cuando termina el periodo de revelación
+    pub fn finalize_sealed_vote(
+        ctx: Context<FinalizeSealedVote>,
+    ) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let community = &ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        require!(vote.vote_type == VoteType::Opinion, VotingSystemError::InvalidVoteType);
+        require!(vote.sealed_results, VotingSystemError::VoteNotSealed);
+        require!(vote.status == VoteStatus::Active, VotingSystemError::VoteNotActive);
+        
+        let reveal_deadline = vote.reveal_deadline.ok_or(VotingSystemError::VoteNotSealed)?;
+        require!(clock.unix_timestamp > reveal_deadline, VotingSystemError::BallotRevealNotEnded);
+        
+        // Sin quorum se usa check_and_fail_expired_vote
+        require!(vote.has_reached_quorum(community.total_members), VotingSystemError::VoteFailedQuorum);
+        
+        vote.status = VoteStatus::Completed;
+        
+        msg!("🎯 Sealed vote completed!");
+        msg!("Vote: {}", vote.question);
+        msg!("Revealed ballots: {}/{}", vote.revealed_ballots, vote.total_votes);
+        for (i, count) in vote.results.iter().enumerate() {
+            msg!("  Option {}: {} votes", i, count);
+        }
+        
+        Ok(())
+    }
+
+    // === VOTACIONES FEDERADAS ===
+    
+    // Convertir una votación recién creada de la padre en federada (remaining_accounts = todas las hijas)
+    pub fn federate_voting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FederateVoting<'info>>,
+    ) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let community = &ctx.accounts.community;
+        
+        require!(
+            vote.vote_type == VoteType::Opinion && vote.total_votes == 0,
+            VotingSystemError::CannotFederateVote
+        );
+        require!(
+            !community.is_sub_community() &&
+                community.child_count > 0 &&
+                ctx.remaining_accounts.len() == community.child_count as usize,
+            VotingSystemError::InvalidFederation
+        );
+        
+        // Snapshot de miembros de toda la federación
+        let mut federated_members = community.total_members;
+        let mut children: Vec<Pubkey> = Vec::new();
+        for account_info in ctx.remaining_accounts.iter() {
+            let child = Account::<Community>::try_from(account_info)?;
+            require!(
+                child.parent == Some(community.key()) && !children.contains(&child.key()),
+                VotingSystemError::InvalidFederation
+            );
+            children.push(child.key());
+            federated_members += child.total_members;
+        }
+        
+        vote.federated_members = Some(federated_members);
+        
+        msg!("🌐 Vote federated!");
+        msg!("Vote: {}", vote.key());
+        msg!("Community: {} + {} sub-communities", community.name, children.len());
+        msg!("Federated members: {}", federated_members);
+        msg!("Required quorum: {}", vote.calculate_required_quorum(community.total_members));
+        
+        Ok(())
+    }
+    
+    // === SESSION KEYS PARA VOTAR SIN POPUP DE WALLET ===
+    
+    pub fn create_session_key(
+        ctx: Context<CreateSessionKey>,
+        session_key: Pubkey,
+        scopes: u8,
+        duration_hours: u32,
+        max_uses: u32,
+        top_up_lamports: u64,
+    ) -> Result<()> {
+        require!(SessionKey::is_valid_scope(scopes), VotingSystemError::InvalidSessionScope);
+        require!(
+            (1..=SessionKey::MAX_DURATION_HOURS).contains(&duration_hours),
+            VotingSystemError::InvalidSessionDuration
+        );
+        require!(
+            (1..=SessionKey::MAX_USES).contains(&max_uses),
+            VotingSystemError::InvalidSessionMaxUses
+        );
+        require!(session_key != ctx.accounts.authority.key(), VotingSystemError::InvalidSessionKey);
+        
+        // La clave efímera paga el rent de las Participation que crea, se puede fondear aquí
+        if top_up_lamports > 0 {
+            let top_up = anchor_lang::system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.session_signer.to_account_info(),
+            };
+            
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                top_up,
+            );
+            
+            anchor_lang::system_program::transfer(cpi_context, top_up_lamports)?;
+        }
+        
+        let session = &mut ctx.accounts.session;
+        let clock = Clock::get()?;
+        
+        session.authority = ctx.accounts.authority.key();
+        session.session_key = session_key;
+        session.community = ctx.accounts.community.key();
+        session.scopes = scopes;
+        session.expires_at = clock.unix_timestamp + (duration_hours as i64 * 3600);
+        session.max_uses = max_uses;
+        session.uses = 0;
+        session.created_at = clock.unix_timestamp;
+        session.bump = ctx.bumps.session;
+        
+        msg!("🔑 Session key created!");
+        msg!("Authority: {}", session.authority);
+        msg!("Session key: {}", session_key);
+        msg!("Community: {}", ctx.accounts.community.name);
+        msg!("Scopes: {:#04b}", scopes);
+        msg!("Expires at: {}", session.expires_at);
+        msg!("Max uses: {}", max_uses);
+        
+        Ok(())
+    }
+    
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        let session = &ctx.accounts.session;
+        
+        // El account se cierra (close = authority) y el rent vuelve a la wallet
+        msg!("🔒 Session key revoked!");
+        msg!("Authority: {}", session.authority);
+        msg!("Session key: {}", session.session_key);
+        msg!("Uses consumed: {}/{}", session.uses, session.max_uses);
+        
+        Ok(())
+    }
+
+    pub fn request_membership(
+        ctx: Context<RequestMembership>,
+        message: String,
+    ) -> Result<()> {
+        require!(message.len() <= 300, VotingSystemError::RequestMessageTooLong);
+        
+        let request = &mut ctx.accounts.membership_request;
+        let community = &ctx.accounts.community;
+        let user = &ctx.accounts.user;
+        let clock = Clock::get()?;
+        
+        // Validaciones
+        require!(community.is_active, VotingSystemError::CommunityInactive);
+        
+        // El PDA se reutiliza si ya hubo una solicitud rechazada o cancelada
+        let is_resubmission = request.user != Pubkey::default();
+        if is_resubmission {
+            match request.status {
+                MembershipRequestStatus::Pending => return err!(VotingSystemError::RequestAlreadyPending),
+                MembershipRequestStatus::Approved => return err!(VotingSystemError::RequestAlreadyApproved),
+                MembershipRequestStatus::Rejected => require!(
+                    clock.unix_timestamp >= request.can_resubmit_at(community.request_cooldown),
+                    VotingSystemError::RequestCooldownActive
+                ),
+                MembershipRequestStatus::Cancelled => {}
+            }
+        }
+        
+        // Inicializar solicitud
+        request.user = ctx.accounts.requester.key();
+        request.community = community.key();
+        request.message = message.clone();
+        request.status = MembershipRequestStatus::Pending;
+        request.requested_at = clock.unix_timestamp;
+        request.reviewed_by = None;
+        request.reviewed_at = None;
+        request.admin_notes = String::new();
+        request.bump = ctx.bumps.membership_request;
+        
+        emit!(MembershipRequested {
+            community: community.key(),
+            user: request.user,
+            message: message.clone(),
+            is_resubmission,
+            requested_at: clock.unix_timestamp,
+        });
+        
+        msg!("🔔 Membership request submitted successfully!");
+        msg!("User: {}", ctx.accounts.requester.key());
+        msg!("Community: {}", community.name);
+        msg!("Message: {}", message);
+        msg!("Requested at: {}", clock.unix_timestamp);
+        msg!("Resubmission: {}", is_resubmission);
+        
+        Ok(())
+    }
+    
+    // Retirar una solicitud pendiente (cierra el account y devuelve el rent)
+    pub fn cancel_membership_request(ctx: Context<CancelMembershipRequest>) -> Result<()> {
+        let request = &ctx.accounts.membership_request;
+        let clock = Clock::get()?;
+        
+        emit!(MembershipRequestCancelled {
+            community: request.community,
+            user: request.user,
+            cancelled_at: clock.unix_timestamp,
+        });
+        
+        msg!("🚫 Membership request cancelled");
+        msg!("User: {}", request.user);
+        msg!("Community: {}", request.community);
+        
+        Ok(())
+    }
+    
+    pub fn approve_membership(
+        ctx: Context<ApproveMembership>,
+        admin_notes: String,
+    ) -> Result<()> {
+        require!(admin_notes.len() <= 200, VotingSystemError::AdminNotesTooLong);
+        
+        let request = &mut ctx.accounts.membership_request;
+        let membership = &mut ctx.accounts.membership;
+        let community = &mut ctx.accounts.community;
+        let admin_membership = &mut ctx.accounts.admin_membership;
+        let clock = Clock::get()?;
+        
+        // Validaciones
+        require!(
+            has_permission(admin_membership, &ctx.accounts.custom_role, 0, PERMISSION_APPROVE_MEMBERS)?,
+            VotingSystemError::InsufficientPermissions
+        );
+        require!(request.status == MembershipRequestStatus::Pending, VotingSystemError::RequestNotPending);
+        
+        // Actualizar solicitud
+        request.status = MembershipRequestStatus::Approved;
+        request.reviewed_by = Some(admin_membership.user);
+        request.reviewed_at = Some(clock.unix_timestamp);
+        request.admin_notes = admin_notes.clone();
+        
+        // Límite de miembros
+        require!(!community.is_full(), VotingSystemError::CommunityFull);
+        
+        // Crear (o reactivar) membership, lo que hacía join_community
+        // En comunidades con cuotas el aprobado debe pagar con renew_membership
+        activate_membership(
+            membership,
+            community,
+            request.user,
+            ctx.bumps.membership,
+            clock.unix_timestamp,
+            community.has_dues().then_some(clock.unix_timestamp),
+        )?;
+        
+        // Crear log de moderación
+        let moderation_log = &mut ctx.accounts.moderation_log;
+        moderation_log.community = community.key();
+        moderation_log.moderator = admin_membership.user;
+        moderation_log.target_user = Some(request.user);
+        moderation_log.target_vote = None;
+        moderation_log.action = ModerationAction::ApproveMembership;
+        moderation_log.reason = admin_notes.clone();
+        moderation_log.executed_at = clock.unix_timestamp;
+        moderation_log.bump = ctx.bumps.moderation_log;
+        
+        emit!(MembershipRequestApproved {
+            community: community.key(),
+            user: request.user,
+            reviewed_by: admin_membership.user,
+            admin_notes,
+            reviewed_at: clock.unix_timestamp,
+        });
+        
+        msg!("✅ Membership request approved successfully!");
+        msg!("User: {}", request.user);
+        msg!("Community: {}", community.name);
+        msg!("Approved by: {}", admin_membership.user);
+        msg!("Total members now: {}", community.total_members);
+        
+        ctx.accounts.community.moderation_log_count += 1;
+        
+        Ok(())
+    }
+    
+    pub fn reject_membership(
+        ctx: Context<RejectMembership>,
+        admin_notes: String,
+    ) -> Result<()> {
+        require!(admin_notes.len() <= 200, VotingSystemError::AdminNotesTooLong);
+        
+        let request = &mut ctx.accounts.membership_request;
+        let admin_membership = &mut ctx.accounts.admin_membership;
+        let clock = Clock::get()?;
+        
+        // Validaciones
+        require!(
+            has_permission(admin_membership, &ctx.accounts.custom_role, 0, PERMISSION_APPROVE_MEMBERS)?,
+            VotingSystemError::InsufficientPermissions
+        );
+        require!(request.status == MembershipRequestStatus::Pending, VotingSystemError::RequestNotPending);
+        
+        // Actualizar solicitud
+        request.status = MembershipRequestStatus::Rejected;
+        request.reviewed_by = Some(admin_membership.user);
+        request.reviewed_at = Some(clock.unix_timestamp);
+        request.admin_notes = admin_notes.clone();
+        
+        // Crear log de moderación
+        let moderation_log = &mut ctx.accounts.moderation_log;
+        moderation_log.community = request.community;
+        moderation_log.moderator = admin_membership.user;
+        moderation_log.target_user = Some(request.user);
+        moderation_log.target_vote = None;
+        moderation_log.action = ModerationAction::RejectMembership;
+        moderation_log.reason = admin_notes.clone();
+        moderation_log.executed_at = clock.unix_timestamp;
+        moderation_log.bump = ctx.bumps.moderation_log;
+        
+        emit!(MembershipRequestRejected {
+            community: request.community,
+            user: request.user,
+            reviewed_by: admin_membership.user,
+            admin_notes,
+            reviewed_at: clock.unix_timestamp,
+            can_resubmit_at: request.can_resubmit_at(ctx.accounts.community.request_cooldown),
+        });
+        
+        msg!("❌ Membership request rejected!");
+        msg!("User: {}", request.user);
+        msg!("Community: {}", request.community);
+        msg!("Rejected by: {}", admin_membership.user);
+        msg!("Reason: {}", request.admin_notes);
+        
+        ctx.accounts.community.moderation_log_count += 1;
+        
+        Ok(())
+    }
+
+    pub fn join_community(ctx: Context<JoinCommunity>) -> Result<()> {
+        // === VALIDACIONES ===
+        require!(ctx.accounts.community.is_active, VotingSystemError::CommunityInactive);
+        
+        // Verificar si la comunidad requiere aprobación
+        require!(!ctx.accounts.community.requires_approval, VotingSystemError::CommunityRequiresApproval);
+        
+        // Límite de miembros (si está llena: join_waitlist)
+        require!(ctx.accounts.community.has_open_seat(), VotingSystemError::CommunityFull);
+        
+        // === INICIALIZAR (O REACTIVAR) MEMBERSHIP ACCOUNT ===
+        let community = &mut ctx.accounts.community;
+        let user = &ctx.accounts.user;
+        let clock = Clock::get()?;
+        
+        // Primer periodo pagado al entrar
+        let mut expires_at = None;
+        if community.has_dues() {
+            charge_membership_dues(
+                &ctx.accounts.member,
+                &ctx.accounts.vault,
+                &ctx.accounts.system_program,
+                community.membership_fee,
+            )?;
+            expires_at = Some(clock.unix_timestamp + community.membership_period);
+        }
+        
+        // Usar member.key() (wallet) no user.key() (PDA)
+        let is_rejoin = activate_membership(
+            &mut ctx.accounts.membership,
+            community,
+            ctx.accounts.member.key(),
+            ctx.bumps.membership,
+            clock.unix_timestamp,
+            expires_at,
+        )?;
+        
+        // === LOGS PARA DEBUGGING ===
+        msg!("✅ User joined community successfully!");
+        msg!("User: {}", user.key());
+        msg!("Rejoin: {}", is_rejoin);
+        msg!("Community: {}", community.name);
+        msg!("Total members now: {}", community.total_members);
+        msg!("Joined at: {}", clock.unix_timestamp);
+        if let Some(expires_at) = ctx.accounts.membership.expires_at {
+            msg!("Membership expires at: {}", expires_at);
+        }
+        
+        Ok(())
+    }
+    
+    // Pagar uno o varios periodos de cuotas (se acumulan sobre el periodo vigente)
+    pub fn renew_membership(
+        ctx: Context<RenewMembership>,
+        periods: u8,
+    ) -> Result<()> {
+        require!(
+            (1..=Community::MAX_RENEWAL_PERIODS).contains(&periods),
+            VotingSystemError::InvalidRenewalPeriods
+        );
+        
+        let community = &ctx.accounts.community;
+        require!(community.has_dues(), VotingSystemError::NoMembershipDues);
+        
+        let clock = Clock::get()?;
+        let amount = community.membership_fee
+            .checked_mul(periods as u64)
+            .ok_or(VotingSystemError::InvalidMembershipFee)?;
+        
+        charge_membership_dues(
+            &ctx.accounts.member,
+            &ctx.accounts.vault,
+            &ctx.accounts.system_program,
+            amount,
+        )?;
+        
+        let membership = &mut ctx.accounts.membership;
+        let start = membership.expires_at.unwrap_or(clock.unix_timestamp).max(clock.unix_timestamp);
+        let new_expiry = start + community.membership_period * periods as i64;
+        membership.expires_at = Some(new_expiry);
+        
+        msg!("🔄 Membership renewed!");
+        msg!("User: {}", membership.user);
+        msg!("Community: {}", community.name);
+        msg!("Periods: {} ({} lamports)", periods, amount);
+        msg!("Expires at: {}", new_expiry);
+        
+        Ok(())
+    }
+    
+    // Publicar (o reemplazar) el Merkle root de wallets invitadas
+    pub fn set_invite_list(
+        ctx: Context<SetInviteList>,
+        merkle_root: [u8; 32],
+        duration_hours: u32,
+    ) -> Result<()> {
+        require!(
+            (1..=InviteList::MAX_DURATION_HOURS).contains(&duration_hours),
+            VotingSystemError::InvalidInviteDuration
+        );
+        
+        let invite_list = &mut ctx.accounts.invite_list;
+        let clock = Clock::get()?;
+        
+        // Un root nuevo invalida todas las pruebas del anterior
+        invite_list.community = ctx.accounts.community.key();
+        invite_list.merkle_root = merkle_root;
+        invite_list.expires_at = clock.unix_timestamp + (duration_hours as i64 * 3600);
+        invite_list.is_revoked = false;
+        invite_list.created_by = ctx.accounts.admin.key();
+        invite_list.updated_at = clock.unix_timestamp;
+        invite_list.bump = ctx.bumps.invite_list;
+        
+        msg!("📨 Invite list published!");
+        msg!("Community: {}", ctx.accounts.community.name);
+        msg!("Expires at: {}", invite_list.expires_at);
+        msg!("Published by: {}", invite_list.created_by);
+        
+        Ok(())
+    }
+    
+    pub fn revoke_invite_list(ctx: Context<RevokeInviteList>) -> Result<()> {
+        let invite_list = &mut ctx.accounts.invite_list;
+        
+        invite_list.is_revoked = true;
+        invite_list.updated_at = Clock::get()?.unix_timestamp;
+        
+        msg!("🚫 Invite list revoked");
+        msg!("Community: {}", ctx.accounts.community.name);
+        msg!("Joined with invite: {}", invite_list.total_joined);
+        
+        Ok(())
+    }
+    
+    // Entrada directa con prueba Merkle (salta la aprobación manual)
+    pub fn join_with_invite(
+        ctx: Context<JoinWithInvite>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(proof.len() <= InviteList::MAX_PROOF_LEN, VotingSystemError::InvalidInviteProof);
+        
+        let invite_list = &mut ctx.accounts.invite_list;
+        let community = &mut ctx.accounts.community;
+        let member = ctx.accounts.member.key();
+        let clock = Clock::get()?;
+        
+        require!(invite_list.is_usable(clock.unix_timestamp), VotingSystemError::InviteListInactive);
+        require!(invite_list.verify(&proof, &member), VotingSystemError::InvalidInviteProof);
+        
+        // Límite de miembros (la cola de espera va primero)
+        require!(community.has_open_seat(), VotingSystemError::CommunityFull);
+        
+        let mut expires_at = None;
+        if community.has_dues() {
+            charge_membership_dues(
+                &ctx.accounts.member,
+                &ctx.accounts.vault,
+                &ctx.accounts.system_program,
+                community.membership_fee,
+            )?;
+            expires_at = Some(clock.unix_timestamp + community.membership_period);
+        }
+        
+        let is_rejoin = activate_membership(
+            &mut ctx.accounts.membership,
+            community,
+            member,
+            ctx.bumps.membership,
+            clock.unix_timestamp,
+            expires_at,
+        )?;
+        invite_list.total_joined += 1;
+        
+        msg!("✅ User joined community with invite!");
+        msg!("User: {}", member);
+        msg!("Rejoin: {}", is_rejoin);
+        msg!("Community: {}", community.name);
+        msg!("Total members now: {}", community.total_members);
+        
+        Ok(())
+    }
+    
+    // Entrar en la cola de una comunidad llena (deposita el rent de la membership)
+    pub fn join_waitlist(ctx: Context<JoinWaitlist>) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let waitlist = &mut ctx.accounts.waitlist;
+        let member = ctx.accounts.member.key();
+        let clock = Clock::get()?;
+        
+        require!(!community.requires_approval, VotingSystemError::CommunityRequiresApproval);
+        require!(!community.has_open_seat(), VotingSystemError::CommunityNotFull);
+        require!(waitlist.position(&member).is_none(), VotingSystemError::AlreadyOnWaitlist);
+        require!(waitlist.entries.len() < Waitlist::MAX_ENTRIES, VotingSystemError::WaitlistFull);
+        
+        // Quien ejecute el crank de admisión recupera este depósito
+        let deposit = Rent::get()?.minimum_balance(8 + Membership::LEN);
+        let deposit_transfer = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.member.to_account_info(),
+            to: waitlist.to_account_info(),
+        };
+        
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            deposit_transfer,
+        );
+        
+        anchor_lang::system_program::transfer(cpi_context, deposit)?;
+        
+        waitlist.community = community.key();
+        waitlist.bump = ctx.bumps.waitlist;
+        waitlist.entries.push(WaitlistEntry {
+            user: member,
+            deposit,
+            joined_at: clock.unix_timestamp,
+        });
+        community.waitlist_count = waitlist.entries.len() as u16;
+        
+        msg!("⏳ User added to waitlist");
+        msg!("User: {}", member);
+        msg!("Community: {}", community.name);
+        msg!("Position: {}", waitlist.entries.len());
+        
+        Ok(())
+    }
+    
+    pub fn leave_waitlist(ctx: Context<LeaveWaitlist>) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let waitlist = &mut ctx.accounts.waitlist;
+        let member = ctx.accounts.member.key();
+        
+        let position = waitlist.position(&member).ok_or(VotingSystemError::NotOnWaitlist)?;
+        let entry = waitlist.entries.remove(position);
+        community.waitlist_count = waitlist.entries.len() as u16;
+        
+        **waitlist.to_account_info().try_borrow_mut_lamports()? -= entry.deposit;
+        **ctx.accounts.member.to_account_info().try_borrow_mut_lamports()? += entry.deposit;
+        
+        msg!("🚪 User left waitlist");
+        msg!("User: {}", member);
+        msg!("Deposit refunded: {} lamports", entry.deposit);
+        
+        Ok(())
+    }
+    
+    // Crank sin permisos: admite al primero de la cola si hay plaza
+    pub fn admit_from_waitlist(ctx: Context<AdmitFromWaitlist>) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let waitlist = &mut ctx.accounts.waitlist;
+        let clock = Clock::get()?;
+        
+        require!(!community.is_full(), VotingSystemError::CommunityFull);
+        require!(community.is_active, VotingSystemError::CommunityInactive);
+        
+        // Si la comunidad pasó a requerir aprobación, la cola ya no da acceso directo
+        // (los que esperan recuperan su depósito con leave_waitlist y piden membresía)
+        require!(!community.requires_approval, VotingSystemError::CommunityRequiresApproval);
+        
+        let entry = waitlist.entries.remove(0);
+        community.waitlist_count = waitlist.entries.len() as u16;
+        
+        // El depósito cubre el rent que acaba de pagar el crank
+        **waitlist.to_account_info().try_borrow_mut_lamports()? -= entry.deposit;
+        **ctx.accounts.payer.to_account_info().try_borrow_mut_lamports()? += entry.deposit;
+        
+        // El crank no puede cobrar al admitido (no firma): con cuotas entra ya vencido
+        // y no puede participar hasta pagar el primer periodo con renew_membership
+        let expires_at = community.has_dues().then_some(clock.unix_timestamp);
+        
+        activate_membership(
+            &mut ctx.accounts.membership,
+            community,
+            entry.user,
+            ctx.bumps.membership,
+            clock.unix_timestamp,
+            expires_at,
+        )?;
+        waitlist.total_admitted += 1;
+        
+        msg!("✅ User admitted from waitlist!");
+        msg!("User: {}", entry.user);
+        msg!("Community: {}", community.name);
+        msg!("Waited since: {}", entry.joined_at);
+        msg!("Remaining in waitlist: {}", waitlist.entries.len());
+        msg!("Total members now: {}", community.total_members);
+        
+        Ok(())
+    }
+    
+    // Salida voluntaria: cierra la membership y devuelve el rent al miembro
+    pub fn leave_community(ctx: Context<LeaveCommunity>) -> Result<()> {
+        let community = &mut ctx.accounts.community;
+        let membership = &ctx.accounts.membership;
+        
+        // La authority debe transferir la comunidad antes de irse
+        require!(
+            membership.user != community.authority,
+            VotingSystemError::AuthorityCannotLeave
+        );
+        
+        // Un baneado no puede borrar su estado saliendo
+        require!(membership.role != UserRole::Banned, VotingSystemError::UserBanned);
+        
+        if membership.is_admin() {
+            require!(community.admin_count > 1, VotingSystemError::LastAdminCannotLeave);
+            community.admin_count -= 1;
+        }
+        
+        if membership.is_active {
+            community.total_members = community.total_members.saturating_sub(1);
+        }
+        community.unlist_moderator(&membership.user);
+        
+        msg!("👋 User left community");
+        msg!("User: {}", membership.user);
+        msg!("Community: {}", community.name);
+        msg!("Role: {:?}", membership.role);
+        msg!("Remaining members: {}", community.total_members);
+        
+        Ok(())
+    }
+
+    pub fn create_knowledge_voting(
+        ctx: Context<CreateKnowledgeVoting>,
+        question: String,
+        options: Vec<String>,
+        vote_type: VoteType,
+        answer_hash: Option<[u8; 32]>,
+        deadline_hours: u32,
+        quorum_required: u64,
+    ) -> Result<()> {
+        // Reutilizar la misma lógica que create_voting
+        // === VALIDACIONES BÁSICAS ===
+        require!(question.len() > 0 && question.len() <= 200, VotingSystemError::QuestionTooLong);
+        require!(options.len() >= 2 && options.len() <= 4, VotingSystemError::InvalidOptionsCount);
+        require!(deadline_hours >= 1 && deadline_hours <= 168, VotingSystemError::InvalidDeadline);
+        require!(quorum_required > 0, VotingSystemError::InvalidQuorum);
+        
+        for option in &options {
+            require!(option.len() > 0 && option.len() <= 50, VotingSystemError::OptionTooLong);
+        }
+        
+        // Validaciones específicas para Knowledge type
+        // La respuesta correcta se compromete como hash y se revela tras el deadline
+        if vote_type == VoteType::Knowledge {
+            require!(answer_hash.is_some(), VotingSystemError::MissingAnswerHash);
+        }
+        
+        // El creador debe ser miembro con las cuotas al día
+        require!(
+            ctx.accounts.membership.is_in_good_standing(Clock::get()?.unix_timestamp),
+            VotingSystemError::MembershipExpired
+        );
+        require!(
+            has_permission(
+                &mut ctx.accounts.membership,
+                &ctx.accounts.custom_role,
+                ctx.accounts.community.member_permissions,
+                PERMISSION_CREATE_VOTE,
+            )?,
+            VotingSystemError::InsufficientPermissions
+        );
+        
+        // === SISTEMA DE FEES (0.01 SOL) ===
+        const VOTING_FEE: u64 = 10_000_000;
+        
+        let fee_transfer = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            fee_transfer,
+        );
+        
+        anchor_lang::system_program::transfer(cpi_context, VOTING_FEE)?;
+        
+        let answer_bond = if vote_type == VoteType::Knowledge {
+            escrow_answer_bond(&ctx.accounts.creator, &ctx.accounts.vote, &ctx.accounts.system_program)?
+        } else {
+            0
+        };
+        
+        // === INICIALIZACIÓN DE VOTE ACCOUNT ===
+        let vote = &mut ctx.accounts.vote;
+        let community = &mut ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        vote.community = community.key();
+        vote.creator = ctx.accounts.creator.key();
+        vote.question = question.clone();
+        vote.vote_type = vote_type;
+        vote.options = options.clone();
+        vote.correct_answer = None; // Knowledge: se fija en reveal_correct_answer
+        vote.participants = Vec::new();
+        vote.results = vec![0; options.len()];
+        vote.total_votes = 0;
+        vote.quorum_required = quorum_required;
+        vote.deadline = clock.unix_timestamp + (deadline_hours as i64 * 3600);
+        vote.status = VoteStatus::Active;
+        vote.fee_per_vote = VOTING_FEE;
+        vote.created_at = clock.unix_timestamp;
+        
+        // Commit-reveal para Knowledge
+        if vote_type == VoteType::Knowledge {
+            vote.answer_hash = answer_hash;
+            vote.reveal_deadline = Some(vote.deadline + Vote::REVEAL_PERIOD);
+        }
+        vote.speed_bonus = None;
+        vote.sealed_results = vote_type == VoteType::Knowledge;
+        vote.revealed_ballots = 0;
+        vote.answer_validated = false;
+        vote.answer_bond = answer_bond;
+        vote.bump = ctx.bumps.vote;
+        
+        // === ACTUALIZAR ESTADÍSTICAS DE COMUNIDAD ===
+        community.total_votes += 1;
+        
+        msg!("✅ Knowledge Voting created successfully!");
+        msg!("Question: {}", question);
+        msg!("Options: {:?}", options);
+        msg!("Type: {:?}", vote_type);
+        
+        Ok(())
+    }
+    
+    // TAREA 2.4.3: Cierre de votación Knowledge al llegar el deadline
+    pub fn close_knowledge_voting(
+        ctx: Context<CloseKnowledgeVoting>,
+    ) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let community = &ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
+        require!(vote.status == VoteStatus::Active, VotingSystemError::VoteNotActive);
+        require!(vote.is_expired(clock.unix_timestamp), VotingSystemError::VoteNotExpired);
+        
+        // Sin quorum se usa check_and_fail_expired_vote
+        require!(vote.has_reached_quorum(community.total_members), VotingSystemError::VoteFailedQuorum);
+        
+        vote.status = VoteStatus::AwaitingReveal;
+        
+        msg!("🔒 Knowledge voting closed - awaiting answer reveal!");
+        msg!("Vote: {}", vote.question);
+        msg!("Total votes: {}", vote.total_votes);
+        if let Some(deadline) = vote.reveal_deadline {
+            msg!("Creator must reveal before: {}", deadline);
+        }
+        
+        Ok(())
+    }
+    
+    // Si el creator no revela a tiempo, la votación falla
+    pub fn fail_unrevealed_vote(
+        ctx: Context<FailUnrevealedVote>,
+    ) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let creator_user = &mut ctx.accounts.creator_user;
+        let clock = Clock::get()?;
+        
+        require!(vote.status == VoteStatus::AwaitingReveal, VotingSystemError::NotAwaitingReveal);
+        
+        let reveal_deadline = vote.reveal_deadline.ok_or(VotingSystemError::NoAnswerHashStored)?;
+        require!(clock.unix_timestamp > reveal_deadline, VotingSystemError::RevealDeadlineNotExpired);
+        
+        vote.status = VoteStatus::Failed;
+        
+        // Misma penalización que una respuesta cuestionada por la comunidad
+        creator_user.reputation_points = creator_user.reputation_points.saturating_sub(5);
+        
+        msg!("⌛ Answer was never revealed - vote failed!");
+        msg!("Vote: {}", vote.question);
+        msg!("Creator: {} (-5 reputación)", vote.creator);
+        msg!("Reveal deadline: {}", reveal_deadline);
+        
+        Ok(())
+    }
+    
+    // Cerrar votaciones finalizadas para recuperar el rent
+    pub fn close_vote(
+        ctx: Context<CloseVote>,
+    ) -> Result<()> {
+        let vote = &ctx.accounts.vote;
+        let clock = Clock::get()?;
+        
+        require!(vote.can_be_closed(clock.unix_timestamp), VotingSystemError::VoteNotClosable);
+        
+        // El account se cierra (close = creator)
+        msg!("🧹 Vote account closed!");
+        msg!("Vote: {}", vote.question);
+        msg!("Final status: {:?}", vote.status);
+        msg!("Rent returned to: {}", vote.creator);
+        
+        Ok(())
+    }
+    
+    // TAREA 2.4.4: Función de revelación de respuesta
+    pub fn reveal_correct_answer(
+        ctx: Context<RevealAnswer>,
+        correct_answer: u8,
+        salt: [u8; 32],
+        answer: String,
+        explanation_hash: Option<[u8; 32]>,
+        explanation_uri: Option<String>,
+    ) -> Result<()> {
+        require!(answer.len() <= 200, VotingSystemError::AnswerTooLong);
+        
+        // La URI debe apuntar a un contenido verificable con el hash
+        if let Some(uri) = &explanation_uri {
+            validate_uri(uri, Vote::MAX_EXPLANATION_URI_LEN)?;
+            require!(explanation_hash.is_some(), VotingSystemError::MissingExplanationHash);
+        }
+        
+        let vote = &mut ctx.accounts.vote;
+        let clock = Clock::get()?;
+        
+        // Validar que es una pregunta de Knowledge
+        require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
+        
+        // Validar que es el creator
+        require!(vote.creator == ctx.accounts.creator.key(), VotingSystemError::InsufficientPermissions);
+        
+        // Validar que la votación ha terminado y espera la revelación
+        require!(vote.status == VoteStatus::AwaitingReveal, VotingSystemError::NotAwaitingReveal);
+        
+        // Validar que hay hash de respuesta para verificar
+        let stored_hash = vote.answer_hash.ok_or(VotingSystemError::NoAnswerHashStored)?;
+        
+        // Verificar que el hash coincide con hash(correct_answer || salt)
+        require!((correct_answer as usize) < vote.options.len(), VotingSystemError::InvalidCorrectAnswer);
+        let answer_hash = Vote::compute_answer_hash(correct_answer, &salt);
+        require!(stored_hash == answer_hash, VotingSystemError::InvalidAnswerHash);
+        
+        // Validar deadline de revelación
+        if let Some(deadline) = vote.reveal_deadline {
+            require!(clock.unix_timestamp <= deadline, VotingSystemError::RevealDeadlineExpired);
+        }
+        
+        // Revelar respuesta e iniciar fase de confianza
+        // (solo se revela una vez: la explicación queda fija para los votantes de confianza)
+        vote.correct_answer = Some(correct_answer);
+        vote.revealed_answer = Some(answer.clone());
+        vote.explanation_hash = explanation_hash;
+        vote.explanation_uri = explanation_uri.clone();
+        vote.status = VoteStatus::ConfidenceVoting;
+        vote.confidence_deadline = Some(clock.unix_timestamp + 86400); // 24 horas
+        
+        msg!("✨ Respuesta revelada para pregunta Knowledge!");
+        msg!("Pregunta: {}", vote.question);
+        msg!("Respuesta correcta: {} ({})", correct_answer, vote.options[correct_answer as usize]);
+        msg!("Explicación: {}", answer);
+        if let Some(uri) = &explanation_uri {
+            msg!("Fuentes: {}", uri);
+        }
+        msg!("Fase de confianza iniciada por 24h");
+        
+        Ok(())
+    }
+    
+    // TAREA 2.5.4: Cada votante con respuesta correcta reclama su bonus tras la validación
+    pub fn claim_knowledge_reward(
+        ctx: Context<ClaimKnowledgeReward>,
+    ) -> Result<()> {
+        let vote = &ctx.accounts.vote;
+        let participation = &mut ctx.accounts.participation;
+        let user = &mut ctx.accounts.user;
+        
+        require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
+        require!(vote.status == VoteStatus::Completed, VotingSystemError::VoteNotCompleted);
+        require!(vote.has_validated_answer(), VotingSystemError::AnswerNotValidated);
+        require!(!participation.reward_claimed, VotingSystemError::RewardAlreadyClaimed);
+        require!(participation.is_revealed, VotingSystemError::BallotNotRevealed);
+        
+        let correct_answer = vote.correct_answer.ok_or(VotingSystemError::VoteNotCompleted)?;
+        require!(participation.option_selected == correct_answer, VotingSystemError::IncorrectAnswer);
+        
+        // Liquidar una sola vez
+        participation.reward_claimed = true;
+        
+        // Bonus base + bonus por rapidez (si la votación lo tiene configurado)
+        let speed_bonus = vote.speed_bonus_for(participation.voted_at);
+        let total_bonus = Vote::KNOWLEDGE_BONUS + speed_bonus;
+        
+        user.reputation_points += total_bonus;
+        user.update_voting_weight();
+        
+        let new_level = (user.reputation_points / 10) + 1;
+        if new_level as u32 > user.level {
+            user.level = new_level as u32;
+            msg!("🎉 User leveled up to level {}!", new_level);
+        }
+        
+        msg!("✅ Correct answer! +{} bonus reputation points.", total_bonus);
+        if speed_bonus > 0 {
+            msg!("⚡ Speed bonus: +{} points", speed_bonus);
+        }
+        msg!("User: {}", user.wallet);
+        msg!("Vote: {}", vote.question);
+        msg!("User reputation: {} points, level: {}", user.reputation_points, user.level);
+        
+        Ok(())
+    }
+    
+    // === TORNEOS DE PREGUNTAS KNOWLEDGE ===
+    
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        name: String,
+        total_rounds: u8,
+        prize_pool: u64,
+    ) -> Result<()> {
+        require!(name.len() <= 50, VotingSystemError::NameTooLong);
+        require!(
+            (1..=Tournament::MAX_ROUNDS).contains(&(total_rounds as usize)),
+            VotingSystemError::InvalidTournamentRounds
+        );
+        
+        // El premio queda depositado en el propio account del torneo
+        if prize_pool > 0 {
+            let prize_transfer = anchor_lang::system_program::Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.tournament.to_account_info(),
+            };
+            
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                prize_transfer,
+            );
+            
+            anchor_lang::system_program::transfer(cpi_context, prize_pool)?;
+        }
+        
+        let tournament = &mut ctx.accounts.tournament;
+        let clock = Clock::get()?;
+        
+        tournament.community = ctx.accounts.community.key();
+        tournament.creator = ctx.accounts.creator.key();
+        tournament.name = name.clone();
+        tournament.total_rounds = total_rounds;
+        tournament.rounds = Vec::new();
+        tournament.prize_pool = prize_pool;
+        tournament.total_participants = 0;
+        tournament.standings = Vec::new();
+        tournament.scoring_deadline = clock.unix_timestamp;
+        tournament.status = TournamentStatus::Active;
+        tournament.created_at = clock.unix_timestamp;
+        tournament.finalized_at = None;
+        tournament.bump = ctx.bumps.tournament;
+        
+        msg!("🏁 Tournament created!");
+        msg!("Name: {}", name);
+        msg!("Community: {}", ctx.accounts.community.name);
+        msg!("Rounds: {}", total_rounds);
+        msg!("Prize pool: {} lamports ({} SOL)", prize_pool, prize_pool as f64 / 1_000_000_000.0);
+        
+        Ok(())
+    }
+    
+    pub fn add_tournament_round(
+        ctx: Context<AddTournamentRound>,
+        question: String,
+        options: Vec<String>,
+        answer_hash: [u8; 32],
+        deadline_hours: u32,
+    ) -> Result<()> {
+        // Mismas validaciones que create_voting
+        require!(!question.is_empty() && question.len() <= 200, VotingSystemError::QuestionTooLong);
+        require!((2..=4).contains(&options.len()), VotingSystemError::InvalidOptionsCount);
+        require!((1..=168).contains(&deadline_hours), VotingSystemError::InvalidDeadline);
+        
+        for option in &options {
+            require!(!option.is_empty() && option.len() <= 50, VotingSystemError::OptionTooLong);
+        }
+        
+        require!(ctx.accounts.tournament.status == TournamentStatus::Active, VotingSystemError::TournamentNotActive);
+        require!(
+            ctx.accounts.tournament.rounds.len() < ctx.accounts.tournament.total_rounds as usize,
+            VotingSystemError::TournamentRoundsFull
+        );
+        
+        let answer_bond = escrow_answer_bond(&ctx.accounts.creator, &ctx.accounts.vote, &ctx.accounts.system_program)?;
+        
+        let tournament = &mut ctx.accounts.tournament;
+        let community = &mut ctx.accounts.community;
+        let vote = &mut ctx.accounts.vote;
+        let clock = Clock::get()?;
+        
+        // Cada ronda es una votación Knowledge con commit-reveal (sin fee: el torneo ya está financiado)
+        vote.community = community.key();
+        vote.creator = ctx.accounts.creator.key();
+        vote.question = question.clone();
+        vote.vote_type = VoteType::Knowledge;
+        vote.options = options.clone();
+        vote.correct_answer = None;
+        vote.participants = Vec::new();
+        vote.results = vec![0; options.len()];
+        vote.total_votes = 0;
+        vote.quorum_required = 1;
+        vote.quorum_percentage = None;
+        vote.use_percentage_quorum = false;
+        vote.deadline = clock.unix_timestamp + (deadline_hours as i64 * 3600);
+        vote.status = VoteStatus::Active;
+        vote.fee_per_vote = 0;
+        vote.created_at = clock.unix_timestamp;
+        vote.answer_hash = Some(answer_hash);
+        vote.revealed_answer = None;
+        vote.explanation_hash = None;
+        vote.explanation_uri = None;
+        vote.federated_members = None;
+        vote.reveal_deadline = Some(vote.deadline + Vote::REVEAL_PERIOD);
+        vote.confidence_votes_for = 0;
+        vote.confidence_votes_against = 0;
+        vote.confidence_weight_for = 0.0;
+        vote.confidence_weight_against = 0.0;
+        vote.confidence_deadline = None;
+        vote.weighted_voting_enabled = false;
+        vote.weighted_results = Vec::new();
+        vote.speed_bonus = None;
+        vote.sealed_results = true;
+        vote.revealed_ballots = 0;
+        vote.answer_validated = false;
+        vote.answer_bond = answer_bond;
+        vote.bump = ctx.bumps.vote;
+        
+        tournament.rounds.push(vote.key());
+        
+        // Revelación + 24h de confianza + periodo para registrar puntuaciones
+        let round_scoring_deadline = vote.deadline + Vote::REVEAL_PERIOD + 86400 + Tournament::SCORING_PERIOD;
+        tournament.scoring_deadline = tournament.scoring_deadline.max(round_scoring_deadline);
+        
+        community.total_votes += 1;
+        
+        msg!("❓ Tournament round added!");
+        msg!("Tournament: {}", tournament.name);
+        msg!("Round: {}/{}", tournament.rounds.len(), tournament.total_rounds);
+        msg!("Question: {}", question);
+        msg!("Deadline: {} hours from now", deadline_hours);
+        
+        Ok(())
+    }
+    
+    pub fn join_tournament(ctx: Context<JoinTournament>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let score = &mut ctx.accounts.score;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == TournamentStatus::Active, VotingSystemError::TournamentNotActive);
+        
+        score.tournament = tournament.key();
+        score.user = ctx.accounts.participant.key();
+        score.correct_answers = 0;
+        score.rounds_scored = 0;
+        score.scored_rounds_mask = 0;
+        score.last_scored_at = 0;
+        score.joined_at = clock.unix_timestamp;
+        score.bump = ctx.bumps.score;
+        
+        tournament.total_participants += 1;
+        
+        msg!("🙋 Joined tournament: {}", tournament.name);
+        msg!("Participant: {}", score.user);
+        msg!("Total participants: {}", tournament.total_participants);
+        
+        Ok(())
+    }
+    
+    // Registrar el resultado de una ronda ya validada en la puntuación del participante
+    pub fn record_tournament_round(ctx: Context<RecordTournamentRound>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let score = &mut ctx.accounts.score;
+        let vote = &ctx.accounts.vote;
+        let participation = &ctx.accounts.participation;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == TournamentStatus::Active, VotingSystemError::TournamentNotActive);
+        require!(
+            clock.unix_timestamp <= tournament.scoring_deadline,
+            VotingSystemError::TournamentScoringClosed
+        );
+        
+        let round = tournament.round_index(&vote.key()).ok_or(VotingSystemError::RoundNotInTournament)?;
+        require!(!score.is_round_scored(round), VotingSystemError::RoundAlreadyScored);
+        
+        // La ronda debe haber pasado por revelación y validación comunitaria
+        require!(vote.status == VoteStatus::Completed, VotingSystemError::VoteNotCompleted);
+        require!(vote.has_validated_answer(), VotingSystemError::AnswerNotValidated);
+        let correct_answer = vote.correct_answer.ok_or(VotingSystemError::VoteNotCompleted)?;
+        
+        require!(participation.is_revealed, VotingSystemError::BallotNotRevealed);
+        
+        score.scored_rounds_mask |= 1 << round;
+        score.rounds_scored += 1;
+        
+        let is_correct = participation.option_selected == correct_answer;
+        if is_correct {
+            score.correct_answers += 1;
+            score.last_scored_at = participation.voted_at;
+            
+            tournament.update_standings(TournamentStanding {
+                user: score.user,
+                correct_answers: score.correct_answers,
+                last_scored_at: score.last_scored_at,
+            });
+        }
+        
+        msg!("📝 Tournament round recorded!");
+        msg!("Tournament: {}", tournament.name);
+        msg!("Participant: {}", score.user);
+        msg!("Round {}: {}", round + 1, if is_correct { "correct" } else { "incorrect" });
+        msg!("Score: {} correct of {} rounds", score.correct_answers, score.rounds_scored);
+        
+        for (i, standing) in tournament.standings.iter().enumerate() {
+            msg!("  #{}: {} ({} correct)", i + 1, standing.user, standing.correct_answers);
+        }
+        
+        Ok(())
+    }
+    
+    // Cerrar el ranking y pagar el premio (remaining_accounts: wallets del top en orden)
+    pub fn finalize_tournament<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeTournament<'info>>,
+    ) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == TournamentStatus::Active, VotingSystemError::TournamentNotActive);
+        require!(!tournament.rounds.is_empty(), VotingSystemError::TournamentStillRunning);
+        require!(
+            clock.unix_timestamp > tournament.scoring_deadline,
+            VotingSystemError::TournamentStillRunning
+        );
+        require!(
+            ctx.remaining_accounts.len() == tournament.standings.len(),
+            VotingSystemError::InvalidPrizeRecipient
+        );
+        
+        // Pagar a cada posición su parte del premio
+        let mut paid = 0u64;
+        for (rank, winner) in ctx.remaining_accounts.iter().enumerate() {
+            require!(
+                winner.key() == tournament.standings[rank].user && winner.is_writable,
+                VotingSystemError::InvalidPrizeRecipient
+            );
+            
+            let prize = tournament.prize_for_rank(rank);
+            **tournament.to_account_info().try_borrow_mut_lamports()? -= prize;
+            **winner.try_borrow_mut_lamports()? += prize;
+            paid += prize;
+            
+            msg!("🥇 #{}: {} wins {} lamports", rank + 1, winner.key(), prize);
+        }
+        
+        // Lo no repartido (posiciones vacías y redondeo) vuelve al creator
+        let remainder = tournament.prize_pool - paid;
+        if remainder > 0 {
+            **tournament.to_account_info().try_borrow_mut_lamports()? -= remainder;
+            **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += remainder;
+        }
+        
+        tournament.status = TournamentStatus::Finalized;
+        tournament.finalized_at = Some(clock.unix_timestamp);
+        
+        msg!("🏆 Tournament finalized: {}", tournament.name);
+        msg!("Participants: {}", tournament.total_participants);
+        msg!("Prize paid: {} lamports, returned to creator: {} lamports", paid, remainder);
+        
+        Ok(())
+    }
+    
+    // ===================================================================
+    // TAREA 2.6.4: SISTEMA DE LEADERBOARDS - ACTUALIZACIÓN AUTOMÁTICA
+    // ===================================================================
+    
+    // TAREA 2.6.1: Inicializar GlobalLeaderboard
+    pub fn initialize_global_leaderboard(
+        ctx: Context<InitializeGlobalLeaderboard>,
+    ) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.global_leaderboard;
+        let clock = Clock::get()?;
+        
+        leaderboard.top_users = Vec::new();
+        leaderboard.last_updated = clock.unix_timestamp;
+        leaderboard.total_users = 0;
+        leaderboard.total_reputation = 0;
+        leaderboard.update_authority = ctx.accounts.authority.key();
+        leaderboard.bump = ctx.bumps.global_leaderboard;
+        
+        msg!("🏆 Global Leaderboard initialized successfully!");
+        msg!("Authority: {}", leaderboard.update_authority);
+        
+        Ok(())
+    }
+    
+    // TAREA 2.6.2: Inicializar CommunityLeaderboard
+    pub fn initialize_community_leaderboard(
+        ctx: Context<InitializeCommunityLeaderboard>,
+    ) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.community_leaderboard;
+        let community = &ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        leaderboard.community = community.key();
+        leaderboard.top_users = Vec::new();
+        leaderboard.last_updated = clock.unix_timestamp;
+        leaderboard.total_votes_cast = 0;
+        leaderboard.total_votations_created = 0;
+        leaderboard.most_active_user = Pubkey::default();
+        leaderboard.bump = ctx.bumps.community_leaderboard;
+        
+        msg!("🏆 Community Leaderboard initialized for: {}", community.name);
+        
+        Ok(())
+    }
+    
+    // TAREA 2.6.4: Actualizar GlobalLeaderboard con usuario
+    pub fn update_global_leaderboard(
+        ctx: Context<UpdateGlobalLeaderboard>,
+    ) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.global_leaderboard;
+        let user = &ctx.accounts.user;
+        let clock = Clock::get()?;
+        
+        // Validar autoridad
+        require!(
+            ctx.accounts.authority.key() == leaderboard.update_authority,
+            VotingSystemError::InsufficientPermissions
+        );
+        
+        // Crear entrada del usuario
+        let entry = LeaderboardEntry::from_user_data(
+            user.wallet,
+            user.reputation_points,
+            user.level,
+            user.voting_weight,
+            user.total_votes_cast,
+            0, // total_votations_created - se calcularía desde otras fuentes
+        );
+        
+        // Actualizar ranking
+        leaderboard.update_user_ranking(user.wallet, entry);
+        leaderboard.last_updated = clock.unix_timestamp;
+        leaderboard.total_users += 1;
+        leaderboard.total_reputation += user.reputation_points;
+        
+        msg!("📈 Global leaderboard updated!");
+        msg!("User: {}", user.wallet);
+        msg!("Reputation: {} points, Level: {}, Weight: {}x", 
+             user.reputation_points, user.level, user.voting_weight);
+        
+        // Mostrar top 3 current
+        for (i, entry) in leaderboard.top_users.iter().take(3).enumerate() {
+            msg!("  #{}: {} ({} pts)", i + 1, entry.user, entry.reputation_points);
+        }
+        
+        Ok(())
+    }
+    
+    // TAREA 2.6.4: Actualizar CommunityLeaderboard con usuario
+    pub fn update_community_leaderboard(
+        ctx: Context<UpdateCommunityLeaderboard>,
+    ) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.community_leaderboard;
+        let user = &ctx.accounts.user;
+        let membership = &ctx.accounts.membership;
+        let community = &ctx.accounts.community;
+        let clock = Clock::get()?;
+        
+        // Validar que el usuario es miembro de la comunidad
+        require!(membership.community == community.key(), VotingSystemError::InvalidCommunity);
+        require!(membership.user == user.wallet, VotingSystemError::InvalidUser);
+        require!(membership.is_active, VotingSystemError::NotCommunityMember);
+        
+        // Crear entrada del usuario (priorizando actividad en la comunidad)
+        let entry = LeaderboardEntry::from_user_data(
+            user.wallet,
+            user.reputation_points,
+            user.level,
+            user.voting_weight,
+            user.total_votes_cast,
+            0, // Se puede añadir conteo de votaciones creadas en esta comunidad
+        );
+        
+        // Actualizar ranking comunitario
+        leaderboard.update_user_ranking(user.wallet, entry);
+        leaderboard.last_updated = clock.unix_timestamp;
+        leaderboard.total_votes_cast = community.total_votes;
+        
+        msg!("🏚️ Community leaderboard updated!");
+        msg!("Community: {}", community.name);
+        msg!("User: {}", user.wallet);
+        msg!("Votes cast: {}, Reputation: {}", user.total_votes_cast, user.reputation_points);
+        
+        // Mostrar top 3 de la comunidad
+        for (i, entry) in leaderboard.top_users.iter().enumerate() {
+            msg!("  #{}: {} ({} votes)", i + 1, entry.user, entry.total_votes_cast);
+        }
+        
+        Ok(())
+    }
+    
+    // TAREA 2.4.5: Votación de confianza
+    pub fn vote_confidence(
+        ctx: Context<VoteConfidence>,
+        is_confident: bool,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Firmante: la wallet del usuario o una session key autorizada por ella
+        authorize_voter(
+            ctx.accounts.user.wallet,
+            ctx.accounts.voter.key(),
+            &mut ctx.accounts.session,
+            SESSION_SCOPE_VOTE_CONFIDENCE,
+            clock.unix_timestamp,
+        )?;
+        
+        // Membresía con cuotas al día
+        require!(
+            ctx.accounts.membership.is_in_good_standing(clock.unix_timestamp),
+            VotingSystemError::MembershipExpired
+        );
+        
+        let vote = &mut ctx.accounts.vote;
+        let user = &mut ctx.accounts.user;
+        let membership = &ctx.accounts.membership;
+        
+        // Validar que es una pregunta de Knowledge
+        require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
+        
+        // Validar que está en fase de confianza
+        require!(vote.status == VoteStatus::ConfidenceVoting, VotingSystemError::NotInConfidencePhase);
+        
+        // Validar que el usuario es miembro activo
+        require!(membership.is_active, VotingSystemError::NotCommunityMember);
+        require!(membership.user == user.wallet, VotingSystemError::InvalidUser);
+        
+        // Validar deadline de confianza
+        if let Some(deadline) = vote.confidence_deadline {
+            require!(clock.unix_timestamp <= deadline, VotingSystemError::ConfidenceDeadlineExpired);
+        }
+        
+        // Solo quien respondió la pregunta puede votar confianza (participation ya validada)
+        // y una sola vez: el PDA ConfidenceParticipation evita duplicados
+        let weight = if vote.weighted_voting_enabled { user.voting_weight } else { 1.0 };
+        
+        let confidence_participation = &mut ctx.accounts.confidence_participation;
+        confidence_participation.user = user.wallet;
+        confidence_participation.vote = vote.key();
+        confidence_participation.is_confident = is_confident;
+        confidence_participation.weight = weight;
+        confidence_participation.voted_at = clock.unix_timestamp;
+        confidence_participation.bump = ctx.bumps.confidence_participation;
+        
+        // TAREA 2.5.5: Puntos de confianza (+/-2)
+        if is_confident {
+            user.reputation_points += 2;
+            vote.confidence_votes_for += 1;
+            vote.confidence_weight_for += weight;
+            msg!("📈 +2 reputación por voto de confianza A FAVOR");
+        } else {
+            if user.reputation_points >= 2 {
+                user.reputation_points -= 2;
+            }
+            vote.confidence_votes_against += 1;
+            vote.confidence_weight_against += weight;
+            msg!("📉 -2 reputación por voto de confianza EN CONTRA");
+        }
+        
+        // Actualizar nivel si es necesario
+        let new_level = (user.reputation_points / 10) + 1;
+        if new_level as u32 > user.level {
+            user.level = new_level as u32;
+            msg!("🎆 ¡Nuevo nivel alcanzado: {}!", user.level);
+        }
+        
+        msg!("🗺️ Voto de confianza registrado!");
+        msg!("Usuario: {}", user.wallet);
+        msg!("Confianza: {}", if is_confident { "A favor" } else { "En contra" });
+        msg!("Reputación total: {}", user.reputation_points);
+        msg!("Votos a favor: {}, En contra: {}", vote.confidence_votes_for, vote.confidence_votes_against);
+        if vote.weighted_voting_enabled {
+            msg!("Peso a favor: {}, En contra: {} (peso del voto: {}x)", 
+                 vote.confidence_weight_for, vote.confidence_weight_against, weight);
+        }
+        
+        Ok(())
+    }
+    
+    // TAREA 2.4.6: Validación comunitaria final
+    pub fn finalize_confidence_voting(
+        ctx: Context<FinalizeConfidenceVoting>,
+    ) -> Result<()> {
+        let vote = &mut ctx.accounts.vote;
+        let creator_user = &mut ctx.accounts.creator_user;
+        let clock = Clock::get()?;
+        
+        // Validar que es una pregunta de Knowledge
+        require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
+        
+        // Validar que está en fase de confianza
+        require!(vote.status == VoteStatus::ConfidenceVoting, VotingSystemError::NotInConfidencePhase);
+        
+        // Validar que ha pasado el deadline
+        if let Some(deadline) = vote.confidence_deadline {
+            require!(clock.unix_timestamp > deadline, VotingSystemError::ConfidenceVotingStillActive);
+        }
+        
+        // Calcular resultado de validación comunitaria (60% threshold)
+        let is_answer_validated = vote.is_answer_validated();
+        
+        // Finalizar votación; las recompensas y los torneos solo cuentan respuestas validadas
+        vote.status = VoteStatus::Completed;
+        vote.answer_validated = is_answer_validated;
+        
+        // Impacto en reputación del creator
+        if is_answer_validated {
+            // Respuesta validada - bonus de reputación
+            creator_user.reputation_points += 10;
+            msg!("✅ Respuesta validada por la comunidad - Creator +10 reputación");
+        } else {
+            // Respuesta cuestionada - penalty
+            if creator_user.reputation_points >= 5 {
+                creator_user.reputation_points -= 5;
+            }
+            msg!("❌ Respuesta cuestionada por la comunidad - Creator -5 reputación");
+        }
+        
+        // Actualizar nivel si es necesario
+        let new_level = (creator_user.reputation_points / 10) + 1;
+        if new_level as u32 > creator_user.level {
+            creator_user.level = new_level as u32;
+            msg!("🎆 ¡Nuevo nivel alcanzado: {}!", creator_user.level);
+        }
+        
+        msg!("🏁 Validación comunitaria finalizada!");
+        msg!("Pregunta: {}", vote.question);
+        msg!("Votos confianza - A favor: {}, En contra: {}", vote.confidence_votes_for, vote.confidence_votes_against);
+        msg!("Resultado: {}", if is_answer_validated { "Validada" } else { "Cuestionada" });
+        
+        Ok(())
+    }
+    
+    // Un votante impugna la respuesta revelada depositando una fianza
+    pub fn open_answer_dispute(
+        ctx: Context<OpenAnswerDispute>,
+        proposed_answer: u8,
+        reason: String,
+        bond: u64,
+    ) -> Result<()> {
+        require!(reason.len() <= 200, VotingSystemError::ReasonTooLong);
+        require!(bond >= AnswerDispute::MIN_BOND, VotingSystemError::DisputeBondTooLow);
+        
+        let vote = &mut ctx.accounts.vote;
+        let clock = Clock::get()?;
+        
+        // Solo durante la fase de confianza, antes de liquidar recompensas
+        require!(vote.status == VoteStatus::ConfidenceVoting, VotingSystemError::DisputeWindowClosed);
+        if let Some(deadline) = vote.confidence_deadline {
+            require!(clock.unix_timestamp <= deadline, VotingSystemError::DisputeWindowClosed);
+        }
+        
+        require!(
+            (proposed_answer as usize) < vote.options.len() && Some(proposed_answer) != vote.correct_answer,
+            VotingSystemError::InvalidProposedAnswer
+        );
+        
+        // La fianza queda depositada en el account de la disputa
+        let bond_transfer = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.challenger.to_account_info(),
+            to: ctx.accounts.dispute.to_account_info(),
+        };
+        
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            bond_transfer,
+        );
+        
+        anchor_lang::system_program::transfer(cpi_context, bond)?;
+        
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.vote = vote.key();
+        dispute.community = vote.community;
+        dispute.challenger = ctx.accounts.challenger.key();
+        dispute.creator = vote.creator;
+        dispute.bond = bond;
+        dispute.creator_bond_posted = false;
+        dispute.proposed_answer = proposed_answer;
+        dispute.reason = reason.clone();
+        dispute.status = DisputeStatus::Open;
+        dispute.resolved_by = None;
+        dispute.opened_at = clock.unix_timestamp;
+        dispute.resolved_at = None;
+        dispute.bump = ctx.bumps.dispute;
+        
+        // Congela confianza y recompensas hasta la resolución
+        vote.status = VoteStatus::Disputed;
+        
+        msg!("⚠️ Answer disputed!");
+        msg!("Vote: {}", vote.question);
+        msg!("Challenger: {}", dispute.challenger);
+        msg!("Proposed answer: {} ({})", proposed_answer, vote.options[proposed_answer as usize]);
+        msg!("Bond: {} lamports", bond);
+        msg!("Reason: {}", reason);
+        
+        Ok(())
+    }
+    
+    // El creator defiende su respuesta igualando la fianza
+    pub fn respond_to_dispute(
+        ctx: Context<RespondToDispute>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let dispute = &ctx.accounts.dispute;
+        
+        require!(dispute.status == DisputeStatus::Open, VotingSystemError::DisputeNotOpen);
+        require!(!dispute.creator_bond_posted, VotingSystemError::CreatorBondAlreadyPosted);
+        require!(
+            dispute.is_response_window_open(clock.unix_timestamp),
+            VotingSystemError::DisputeResponseWindowClosed
+        );
+        
+        let bond = dispute.bond;
+        let bond_transfer = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: ctx.accounts.dispute.to_account_info(),
+        };
+        
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            bond_transfer,
+        );
+        
+        anchor_lang::system_program::transfer(cpi_context, bond)?;
+        
+        ctx.accounts.dispute.creator_bond_posted = true;
+        
+        msg!("🛡️ Creator responded to dispute!");
+        msg!("Dispute: {}", ctx.accounts.dispute.key());
+        msg!("Bond matched: {} lamports", bond);
+        
+        Ok(())
+    }
+    
+    // Un moderador resuelve: las fianzas van a la parte ganadora
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        uphold_answer: bool,
+        corrected_answer: Option<u8>,
+    ) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        let vote = &mut ctx.accounts.vote;
+        let creator_user = &mut ctx.accounts.creator_user;
+        let moderator = ctx.accounts.moderator.key();
+        let clock = Clock::get()?;
+        
+        require!(dispute.status == DisputeStatus::Open, VotingSystemError::DisputeNotOpen);
+        require!(dispute.can_be_resolved(clock.unix_timestamp), VotingSystemError::DisputeResponsePending);
+        require!(
+            moderator != dispute.challenger && moderator != dispute.creator,
+            VotingSystemError::CannotResolveOwnDispute
+        );
+        
+        if let Some(answer) = corrected_answer {
+            require!(
+                !uphold_answer && (answer as usize) < vote.options.len(),
+                VotingSystemError::InvalidProposedAnswer
+            );
+        }
+        
+        // Fianzas depositadas (challenger + creator si respondió)
+        let total_bonds = if dispute.creator_bond_posted { dispute.bond * 2 } else { dispute.bond };
+        
+        let winner = if uphold_answer {
+            // Respuesta confirmada: se liquida como una validación normal
+            vote.status = VoteStatus::Completed;
+            vote.answer_validated = true;
+            creator_user.reputation_points += 10;
+            dispute.status = DisputeStatus::Upheld;
+            ctx.accounts.creator.to_account_info()
+        } else {
+            // Respuesta anulada: se corrige (las recompensas siguen la nueva) o la pregunta falla
+            match corrected_answer {
+                Some(answer) => {
+                    vote.correct_answer = Some(answer);
+                    vote.status = VoteStatus::Completed;
+                    vote.answer_validated = true;
+                }
+                None => {
+                    vote.status = VoteStatus::Failed;
+                    vote.answer_validated = false;
+                }
+            }
+            creator_user.reputation_points = creator_user
+                .reputation_points
+                .saturating_sub(AnswerDispute::CREATOR_SLASH_REPUTATION);
+            dispute.status = DisputeStatus::Overturned;
+            ctx.accounts.challenger.to_account_info()
+        };
+        creator_user.update_voting_weight();
+        
+        **dispute.to_account_info().try_borrow_mut_lamports()? -= total_bonds;
+        **winner.try_borrow_mut_lamports()? += total_bonds;
+        
+        // Respuesta anulada: la fianza de la pregunta también pasa al challenger
+        let forfeited_bond = if dispute.status == DisputeStatus::Overturned { vote.answer_bond } else { 0 };
+        if forfeited_bond > 0 {
+            **vote.to_account_info().try_borrow_mut_lamports()? -= forfeited_bond;
+            **winner.try_borrow_mut_lamports()? += forfeited_bond;
+            vote.answer_bond = 0;
+        }
+        
+        dispute.resolved_by = Some(moderator);
+        dispute.resolved_at = Some(clock.unix_timestamp);
+        
+        msg!("⚖️ Dispute resolved!");
+        msg!("Vote: {}", vote.question);
+        msg!("Moderator: {}", moderator);
+        msg!("Ruling: {:?}", dispute.status);
+        msg!("Bonds paid: {} lamports to {}", total_bonds, winner.key());
+        
+        if dispute.status == DisputeStatus::Overturned {
+            msg!("❌ Creator -{} reputación", AnswerDispute::CREATOR_SLASH_REPUTATION);
+            msg!("Answer bond forfeited: {} lamports", forfeited_bond);
+            match vote.correct_answer {
+                Some(answer) if vote.status == VoteStatus::Completed => {
+                    msg!("Corrected answer: {} ({})", answer, vote.options[answer as usize]);
+                }
+                _ => msg!("Question voided - no knowledge rewards"),
+            }
+        } else {
+            msg!("✅ Respuesta confirmada - Creator +10 reputación");
+        }
+        
+        Ok(())
+    }
+    
+    // Cerrar una disputa ya resuelta: el rent vuelve al challenger que la abrió
+    pub fn close_answer_dispute(
+        ctx: Context<CloseAnswerDispute>,
+    ) -> Result<()> {
+        let dispute = &ctx.accounts.dispute;
+        
+        // El account se cierra (close = challenger)
+        msg!("🧹 Dispute account closed!");
+        msg!("Vote: {}", dispute.vote);
+        msg!("Ruling: {:?}", dispute.status);
+        msg!("Rent returned to: {}", dispute.challenger);
+        
+        Ok(())
+    }
+}
+
+// ============================================================================
+// FUNCIONES AUXILIARES
+// ============================================================================
+
+// Validar URIs a contenido off-chain (Arweave, IPFS o HTTPS)
+fn validate_uri(uri: &str, max_len: usize) -> Result<()> {
+    const ALLOWED_SCHEMES: [&str; 3] = ["ar://", "ipfs://", "https://"];
+    
+    require!(uri.len() <= max_len, VotingSystemError::InvalidUri);
+    
+    let scheme = ALLOWED_SCHEMES.iter().find(|scheme| uri.starts_with(*scheme));
+    let Some(scheme) = scheme else {
+        return err!(VotingSystemError::InvalidUri);
+    };
+    
+    // Algo más que el esquema y sin espacios ni caracteres de control
+    require!(
+        uri.len() > scheme.len() && !uri.chars().any(|c| c.is_whitespace() || c.is_control()),
+        VotingSystemError::InvalidUri
+    );
+    
+    Ok(())
+}
+
+// Fees retirables del vault: todo lo que supera el mínimo rent-exempt
+fn vault_available_balance(vault: &SystemAccount) -> Result<u64> {
+    let rent_minimum = Rent::get()?.minimum_balance(0);
+    Ok(vault.lamports().saturating_sub(rent_minimum))
+}
+
+// Transferir desde el vault de la comunidad firmando con sus seeds
+fn transfer_from_vault<'info>(
+    vault: &SystemAccount<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    community: Pubkey,
+    vault_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[b"community_vault", community.as_ref(), &[vault_bump]]];
+    
+    let vault_transfer = anchor_lang::system_program::Transfer {
+        from: vault.to_account_info(),
+        to: to.clone(),
+    };
+    
+    let cpi_context = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        vault_transfer,
+        signer_seeds,
+    );
+    
+    anchor_lang::system_program::transfer(cpi_context, amount)
+}
+
+// Fianza de una pregunta Knowledge: queda en el account del vote y vuelve al creator
+// al cerrarlo, salvo que una disputa anule la respuesta
+// Archivar la comunidad vaciando el vault por completo (incluido el mínimo de rent: la cuenta desaparece)
+fn settle_and_archive<'info>(
+    community: &mut Account<'info, Community>,
+    vault: &SystemAccount<'info>,
+    authority: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    vault_bump: u8,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    let pending_fees = vault.lamports();
+    if pending_fees > 0 {
+        transfer_from_vault(
+            vault,
+            authority,
+            system_program,
+            community.key(),
+            vault_bump,
+            pending_fees,
+        )?;
+    }
+    
+    community.is_active = false;
+    community.archived_at = Some(clock.unix_timestamp);
+    community.pending_authority = None;
+    
+    msg!("📦 Community archived!");
+    msg!("Community: {}", community.name);
+    msg!("Fees settled: {} lamports to {}", pending_fees, community.authority);
+    msg!("Members to close: {}", community.total_members);
+    
+    Ok(())
+}
+
+fn escrow_answer_bond<'info>(
+    creator: &Signer<'info>,
+    vote: &Account<'info, Vote>,
+    system_program: &Program<'info, System>,
+) -> Result<u64> {
+    let bond_transfer = anchor_lang::system_program::Transfer {
+        from: creator.to_account_info(),
+        to: vote.to_account_info(),
+    };
+    
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        bond_transfer,
+    );
+    
+    anchor_lang::system_program::transfer(cpi_context, AnswerDispute::CREATOR_ANSWER_BOND)?;
+    
+    Ok(AnswerDispute::CREATOR_ANSWER_BOND)
+}
+
+// Validar y aplicar cambios de configuración (update_community y consejo)
+fn apply_community_settings(community: &mut Community, settings: &CommunitySettings) -> Result<()> {
+    require!(
+        settings.category.is_some() ||
+        settings.quorum_percentage.is_some() ||
+        settings.requires_approval.is_some() ||
+        settings.is_active.is_some() ||
+        settings.cancel_penalty_percentage.is_some() ||
+        settings.membership_fee.is_some() ||
+        settings.membership_period.is_some() ||
+        settings.max_members.is_some() ||
+        settings.member_permissions.is_some() ||
+        settings.request_cooldown.is_some(),
+        VotingSystemError::NoSettingsToUpdate
+    );
+    
+    // Validar el resultado final con las mismas reglas que create_community
+    let category = settings.category.unwrap_or(community.category);
+    let quorum_percentage = settings.quorum_percentage.unwrap_or(community.quorum_percentage);
+    validate_community_settings(category, quorum_percentage)?;
+    
+    if let Some(penalty) = settings.cancel_penalty_percentage {
+        require!(penalty <= 100, VotingSystemError::InvalidPenaltyPercentage);
+    }
+    if let Some(fee) = settings.membership_fee {
+        // Acotada para que renew_membership con el máximo de periodos no desborde
+        require!(fee <= Community::MAX_MEMBERSHIP_FEE, VotingSystemError::InvalidMembershipFee);
+    }
+    if let Some(period) = settings.membership_period {
+        require!(
+            period == 0 || (Community::MIN_MEMBERSHIP_PERIOD..=Community::MAX_MEMBERSHIP_PERIOD).contains(&period),
+            VotingSystemError::InvalidMembershipPeriod
+        );
+    }
+    if let Some(max_members) = settings.max_members {
+        require!(max_members <= community.tier.max_members(), VotingSystemError::InvalidMemberCap);
+    }
+    if let Some(permissions) = settings.member_permissions {
+        require!(
+            permissions & !MEMBER_GRANTABLE_PERMISSIONS == 0,
+            VotingSystemError::InvalidPermissions
+        );
+    }
+    if let Some(cooldown) = settings.request_cooldown {
+        require!(
+            (0..=Community::MAX_REQUEST_COOLDOWN).contains(&cooldown),
+            VotingSystemError::InvalidRequestCooldown
+        );
+    }
+    
+    msg!("⚙️ Updating community '{}'", community.name);
+    
+    if let Some(category) = settings.category {
+        msg!("Category: {} → {}", community.category, category);
+        community.category = category;
+    }
+    if let Some(quorum_percentage) = settings.quorum_percentage {
+        msg!("Quorum: {}% → {}%", community.quorum_percentage, quorum_percentage);
+        community.quorum_percentage = quorum_percentage;
+    }
+    if let Some(requires_approval) = settings.requires_approval {
+        require!(
+            requires_approval || !community.tier.forces_approval(),
+            VotingSystemError::PrivateCommunityRequiresApproval
+        );
+        msg!("Requires approval: {} → {}", community.requires_approval, requires_approval);
+        community.requires_approval = requires_approval;
+    }
+    if let Some(is_active) = settings.is_active {
+        // Archivar es definitivo
+        require!(!(is_active && community.is_archived()), VotingSystemError::CommunityArchived);
+        msg!("Active: {} → {}", community.is_active, is_active);
+        community.is_active = is_active;
+    }
+    if let Some(penalty) = settings.cancel_penalty_percentage {
+        msg!("Cancel penalty: {}% → {}%", community.cancel_penalty_percentage, penalty);
+        community.cancel_penalty_percentage = penalty;
+    }
+    if let Some(fee) = settings.membership_fee {
+        msg!("Membership fee: {} → {} lamports", community.membership_fee, fee);
+        community.membership_fee = fee;
+    }
+    if let Some(period) = settings.membership_period {
+        // Las memberships existentes conservan su expires_at actual
+        msg!("Membership period: {}s → {}s", community.membership_period, period);
+        community.membership_period = period;
+    }
+    if let Some(max_members) = settings.max_members {
+        // Bajar el límite no expulsa a nadie, solo bloquea nuevas entradas
+        let max_members = (max_members > 0).then_some(max_members);
+        msg!("Max members: {:?} → {:?}", community.max_members, max_members);
+        community.max_members = max_members;
+    }
+    if let Some(permissions) = settings.member_permissions {
+        msg!("Member permissions: {:#06b} → {:#06b}", community.member_permissions, permissions);
+        community.member_permissions = permissions;
+    }
+    if let Some(cooldown) = settings.request_cooldown {
+        msg!("Request cooldown: {}s → {}s", community.request_cooldown, cooldown);
+        community.request_cooldown = cooldown;
+    }
+    
+    Ok(())
+}
+
+// Validar categoría y quorum de una comunidad (creación y update_community)
+fn validate_community_settings(category: u8, quorum_percentage: u8) -> Result<()> {
+    require!((1..=100).contains(&quorum_percentage), VotingSystemError::InvalidQuorum);
+    require!(VotingCategory::from_u8(category).is_some(), VotingSystemError::InvalidCategory);
+    
+    Ok(())
+}
+
+// Verificar que el firmante es la wallet del usuario o una session key válida
+fn authorize_voter(
+    wallet: Pubkey,
+    signer: Pubkey,
+    session: &mut Option<Account<SessionKey>>,
+    scope: u8,
+    current_timestamp: i64,
+) -> Result<()> {
+    if signer == wallet {
+        return Ok(());
+    }
+    
+    // Las seeds del account ya garantizan authority == wallet y session_key == signer
+    let session = session.as_mut().ok_or(VotingSystemError::InvalidUser)?;
+    require!(session.allows(scope), VotingSystemError::SessionScopeNotAllowed);
+    require!(!session.is_expired(current_timestamp), VotingSystemError::SessionExpired);
+    require!(session.has_uses_left(), VotingSystemError::SessionUsesExhausted);
+    
+    session.uses += 1;
+    msg!("🔑 Signed with session key ({}/{} uses)", session.uses, session.max_uses);
+    
+    Ok(())
+}
+
+// Reglas de membresía que una sub-comunidad copia de su padre
+fn inherit_parent_rules(community: &mut Community, parent: &Community) {
+    community.requires_approval |= parent.requires_approval;
+    community.membership_fee = parent.membership_fee;
+    community.membership_period = parent.membership_period;
+    community.member_permissions = parent.member_permissions;
+    community.request_cooldown = parent.request_cooldown;
+}
+
+// Admin de la comunidad o de su comunidad padre
+fn can_administer(community: &Account<Community>, admin_membership: &Membership) -> bool {
+    admin_membership.community == community.key() ||
+        community.parent == Some(admin_membership.community)
+}
+
+// Miembro de la comunidad del voto o, si es federado, de una de sus hijas
+fn is_eligible_voter(
+    vote: &Vote,
+    membership: &Membership,
+    member_community: &Option<Account<Community>>,
+) -> bool {
+    if membership.community == vote.community {
+        return true;
+    }
+    
+    vote.is_federated() && member_community.as_ref().is_some_and(|child| {
+        child.key() == membership.community && child.parent == Some(vote.community)
+    })
+}
+
+// Permisos efectivos de una membership (la dirección del rol personalizado se valida en el contexto)
+fn has_permission(
+    membership: &mut Membership,
+    custom_role: &Option<UncheckedAccount>,
+    member_permissions: u16,
+    permission: u16,
+) -> Result<bool> {
+    let custom_role_permissions = match custom_role {
+        Some(role_info) => custom_role_permissions(membership, role_info)?,
+        None => 0,
+    };
+    Ok(membership.permissions(member_permissions, custom_role_permissions) & permission == permission)
+}
+
+// Permisos del rol asignado; si se borró (o se recreó con el mismo nombre después de asignarlo)
+// la referencia queda obsoleta y se limpia en lugar de bloquear la instrucción
+fn custom_role_permissions(membership: &mut Membership, role_info: &UncheckedAccount) -> Result<u16> {
+    if !role_info.data_is_empty() {
+        let role = CommunityRole::try_deserialize(&mut &role_info.try_borrow_data()?[..])?;
+        if role.created_at <= membership.custom_role_assigned_at {
+            return Ok(role.permissions);
+        }
+    }
+    
+    msg!("🏷️ Stale community role cleared: {}", role_info.key());
+    membership.custom_role = None;
+    Ok(0)
+}
+
+// Cobrar cuotas de membresía al vault de la comunidad
+fn charge_membership_dues<'info>(
+    member: &Signer<'info>,
+    vault: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    let dues_transfer = anchor_lang::system_program::Transfer {
+        from: member.to_account_info(),
+        to: vault.to_account_info(),
+    };
+    
+    let cpi_context = CpiContext::new(system_program.to_account_info(), dues_transfer);
+    anchor_lang::system_program::transfer(cpi_context, amount)?;
+    
+    msg!("💳 Membership dues paid: {} lamports", amount);
+    Ok(())
+}
+
+// Crear o reactivar una membership (la PDA existe si el usuario fue removido)
+fn activate_membership(
+    membership: &mut Membership,
+    community: &mut Account<Community>,
+    member: Pubkey,
+    bump: u8,
+    current_timestamp: i64,
+    expires_at: Option<i64>,
+) -> Result<bool> {
+    let is_rejoin = membership.user != Pubkey::default();
+    if is_rejoin {
+        require!(!membership.is_active, VotingSystemError::AlreadyMember);
+        require!(membership.role != UserRole::Banned, VotingSystemError::UserBanned);
+    }
+    
+    membership.user = member;
+    membership.community = community.key();
+    membership.role = UserRole::Member; // Rol por defecto
+    membership.joined_at = current_timestamp;
+    membership.is_active = true;
+    membership.expires_at = expires_at;
+    membership.custom_role = None;
+    membership.bump = bump;
+    
+    community.total_members += 1;
+    
+    Ok(is_rejoin)
+}
+
+// Calcular reward basado en reputación del usuario
+fn calculate_user_reward(reputation_points: u64, total_distribution: u64) -> u64 {
+    // Solo usuarios con 100+ puntos de reputación pueden reclamar rewards
+    if reputation_points < 100 {
+        return 0;
+    }
+    
+    // Sistema de tiers basado en reputación
+    let reward_multiplier = if reputation_points >= 5000 {
+        10 // VIP: 10% del pool
+    } else if reputation_points >= 1000 {
+        5  // Premium: 5% del pool
+    } else if reputation_points >= 500 {
+        3  // Advanced: 3% del pool
+    } else {
+        1  // Basic: 1% del pool
+    };
+    
+    // Calcular reward como porcentaje del pool diario
+    (total_distribution * reward_multiplier) / 100
+}
+
+#[derive(Accounts)]
+pub struct Initialize {}
+
+#[derive(Accounts)]
+pub struct InitializeFeePool<'info> {
+    #[account(
+        init,
+        seeds = [b"fee_pool"],
+        bump,
+        space = 8 + FeePool::LEN,
+        payer = authority
+    )]
+    pub fee_pool: Account<'info, FeePool>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateUser<'info> {
+    #[account(
+        init,
+        seeds = [b"user", wallet.key().as_ref()],
+        bump,
+        space = 8 + User::LEN,
+        payer = wallet
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FederateVoting<'info> {
+    #[account(
+        mut,
+        constraint = vote.creator == creator.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = vote.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = vote.status == VoteStatus::Active @ VotingSystemError::VoteNotActive
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    pub community: Account<'info, Community>,
+    
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(option_selected: u8)]
+pub struct CastVote<'info> {
+    #[account(
+        init,
+        seeds = [b"participation", vote.key().as_ref(), user.wallet.as_ref()],
+        bump,
+        space = 8 + Participation::LEN,
+        payer = voter
+    )]
+    pub participation: Account<'info, Participation>,
+    
+    #[account(
+        mut,
+        constraint = vote.status == VoteStatus::Active @ VotingSystemError::VoteNotActive
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        constraint = is_eligible_voter(&vote, &membership, &member_community) @ VotingSystemError::NotCommunityMember,
+        constraint = membership.user == user.wallet @ VotingSystemError::NotCommunityMember,
+        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    // Sub-comunidad del votante (solo en votos federados)
+    pub member_community: Option<Account<'info, Community>>,
+    
+    // user.wallet == voter o session válida: se verifica en authorize_voter
+    #[account(mut)]
+    pub user: Account<'info, User>,
+    
+    // Wallet del usuario o session key efímera
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", vote.community.as_ref(), user.wallet.as_ref(), voter.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitVote<'info> {
+    #[account(
+        init,
+        seeds = [b"participation", vote.key().as_ref(), user.wallet.as_ref()],
+        bump,
+        space = 8 + Participation::LEN,
+        payer = voter
+    )]
+    pub participation: Account<'info, Participation>,
+    
+    #[account(
+        mut,
+        constraint = vote.status == VoteStatus::Active @ VotingSystemError::VoteNotActive
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        constraint = is_eligible_voter(&vote, &membership, &member_community) @ VotingSystemError::NotCommunityMember,
+        constraint = membership.user == user.wallet @ VotingSystemError::NotCommunityMember,
+        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    // Sub-comunidad del votante (solo en votos federados)
+    pub member_community: Option<Account<'info, Community>>,
+    
+    // user.wallet == voter o session válida: se verifica en authorize_voter
+    #[account(mut)]
+    pub user: Account<'info, User>,
+    
+    // Wallet del usuario o session key efímera
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"session", vote.community.as_ref(), user.wallet.as_ref(), voter.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealBallot<'info> {
+    #[account(
+        mut,
+        seeds = [b"participation", vote.key().as_ref(), participation.user.as_ref()],
+        bump = participation.bump
+    )]
+    pub participation: Account<'info, Participation>,
+    
+    #[account(mut)]
+    pub vote: Account<'info, Vote>,
+    
+    // El commitment liga la opción a la wallet: el firmante solo paga la transacción
+    pub revealer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSealedVote<'info> {
+    #[account(mut)]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        constraint = community.key() == vote.community @ VotingSystemError::InvalidCommunity
+    )]
+    pub community: Account<'info, Community>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSessionKey<'info> {
+    #[account(
+        init,
+        seeds = [b"session", community.key().as_ref(), authority.key().as_ref(), session_key.as_ref()],
+        bump,
+        space = 8 + SessionKey::LEN,
+        payer = authority
+    )]
+    pub session: Account<'info, SessionKey>,
+    
+    #[account(
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key() @ VotingSystemError::NotCommunityMember,
+        constraint = membership.user == authority.key() @ VotingSystemError::NotCommunityMember,
+        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    /// CHECK: Clave efímera que recibe el top-up, solo se valida la dirección
+    #[account(
+        mut,
+        address = session_key @ VotingSystemError::InvalidSessionKey
+    )]
+    pub session_signer: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"session", session.community.as_ref(), authority.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct JoinCommunity<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"membership", community.key().as_ref(), member.key().as_ref()],
+        bump,
+        space = 8 + Membership::LEN,
+        payer = member
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(
+        constraint = user.wallet == member.key() @ VotingSystemError::InvalidUser
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub member: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveCommunity<'info> {
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), member.key().as_ref()],
+        bump = membership.bump,
+        close = member
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct JoinWaitlist<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"waitlist", community.key().as_ref()],
+        bump,
+        space = 8 + Waitlist::LEN,
+        payer = member
+    )]
+    pub waitlist: Account<'info, Waitlist>,
+    
+    #[account(
+        mut,
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Solo quien no tiene membership (la PDA todavía no existe)
+    #[account(
+        seeds = [b"membership", community.key().as_ref(), member.key().as_ref()],
+        bump,
+        constraint = membership.data_is_empty() @ VotingSystemError::AlreadyMember
+    )]
+    pub membership: SystemAccount<'info>,
+    
+    #[account(
+        constraint = user.wallet == member.key() @ VotingSystemError::InvalidUser
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub member: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveWaitlist<'info> {
+    #[account(
+        mut,
+        seeds = [b"waitlist", community.key().as_ref()],
+        bump = waitlist.bump
+    )]
+    pub waitlist: Account<'info, Waitlist>,
+    
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdmitFromWaitlist<'info> {
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"waitlist", community.key().as_ref()],
+        bump = waitlist.bump,
+        constraint = waitlist.next_user().is_some() @ VotingSystemError::WaitlistEmpty
+    )]
+    pub waitlist: Account<'info, Waitlist>,
+    
+    /// CHECK: Primer usuario de la cola, solo se usa su dirección para las seeds
+    #[account(
+        constraint = waitlist.next_user() == Some(candidate.key()) @ VotingSystemError::NotOnWaitlist
+    )]
+    pub candidate: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        seeds = [b"membership", community.key().as_ref(), candidate.key().as_ref()],
+        bump,
+        space = 8 + Membership::LEN,
+        payer = payer
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    // Cualquiera puede ejecutar el crank; recupera el rent del depósito
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RenewMembership<'info> {
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), member.key().as_ref()],
+        bump = membership.bump,
+        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+        // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub member: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetInviteList<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"invite_list", community.key().as_ref()],
+        bump,
+        space = 8 + InviteList::LEN,
+        payer = admin
+    )]
+    pub invite_list: Account<'info, InviteList>,
+    
+    #[account(
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeInviteList<'info> {
+    #[account(
+        mut,
+        seeds = [b"invite_list", community.key().as_ref()],
+        bump = invite_list.bump
+    )]
+    pub invite_list: Account<'info, InviteList>,
+    
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct JoinWithInvite<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"membership", community.key().as_ref(), member.key().as_ref()],
+        bump,
+        space = 8 + Membership::LEN,
+        payer = member
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"invite_list", community.key().as_ref()],
+        bump = invite_list.bump
+    )]
+    pub invite_list: Account<'info, InviteList>,
+    
+    #[account(
+        mut,
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(
+        constraint = user.wallet == member.key() @ VotingSystemError::InvalidUser
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub member: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, category: u8, quorum_percentage: u8)]
+pub struct CreateCommunity<'info> {
+    #[account(
+        init,
+        seeds = [b"community", authority.key().as_ref(), name.as_bytes()],
+        bump,
+        space = 8 + Community::LEN,
+        payer = authority
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        init,
+        seeds = [b"membership", community.key().as_ref(), authority.key().as_ref()],
+        bump,
+        space = 8 + Membership::LEN,
+        payer = authority
+    )]
+    pub creator_membership: Account<'info, Membership>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_pool"],
+        bump = fee_pool.bump
+    )]
+    pub fee_pool: Account<'info, FeePool>,
+    
+    // Solo para sub-comunidades: padre y membership Admin del creador en ella
+    #[account(mut)]
+    pub parent: Option<Account<'info, Community>>,
+    
+    pub parent_admin_membership: Option<Account<'info, Membership>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCommunity<'info> {
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(
+        init,
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
+        bump,
+        space = 8 + ModerationLog::LEN,
+        payer = admin
+    )]
+    pub moderation_log: Account<'info, ModerationLog>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCommunityProfile<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"community_profile", community.key().as_ref()],
+        bump,
+        space = 8 + CommunityProfile::LEN,
+        payer = admin
+    )]
+    pub profile: Account<'info, CommunityProfile>,
+    
+    #[account(
+        constraint = !community.is_archived() @ VotingSystemError::CommunityArchived
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        seeds = [b"membership", community.key().as_ref(), new_authority_membership.user.as_ref()],
+        bump = new_authority_membership.bump,
+        constraint = new_authority_membership.is_active @ VotingSystemError::NotCommunityMember
+    )]
+    pub new_authority_membership: Account<'info, Membership>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), new_authority.key().as_ref()],
+        bump = new_authority_membership.bump,
+        constraint = new_authority_membership.is_active @ VotingSystemError::NotCommunityMember
+    )]
+    pub new_authority_membership: Account<'info, Membership>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageAdmin<'info> {
+    // Con consejo, la authority sola no puede cambiar quién es admin (desmontaría el M-de-N)
+    #[account(
+        mut,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = community.council.is_none() @ VotingSystemError::CouncilApprovalRequired
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), membership.user.as_ref()],
+        bump = membership.bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAdminCouncil<'info> {
+    #[account(
+        init,
+        seeds = [b"council", community.key().as_ref()],
+        bump,
+        space = 8 + AdminCouncil::LEN,
+        payer = authority
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = community.council.is_none() @ VotingSystemError::CouncilApprovalRequired
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeCouncilAction<'info> {
+    #[account(
+        init,
+        seeds = [b"pending_action", council.key().as_ref(), &council.action_nonce.to_le_bytes()],
+        bump,
+        space = 8 + PendingAction::LEN,
+        payer = proposer
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    #[account(
+        mut,
+        seeds = [b"council", council.community.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    // Se revalida que el proponente siga siendo admin activo
+    #[account(
+        seeds = [b"membership", council.community.as_ref(), proposer.key().as_ref()],
+        bump = proposer_membership.bump,
+        constraint = proposer_membership.is_active && proposer_membership.is_admin() @ VotingSystemError::NotCouncilMember
+    )]
+    pub proposer_membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveCouncilAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"pending_action", council.key().as_ref(), &pending_action.nonce.to_le_bytes()],
+        bump = pending_action.bump
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    pub council: Account<'info, AdminCouncil>,
+    
+    // Se revalida que el miembro siga siendo admin activo al aprobar
+    #[account(
+        seeds = [b"membership", council.community.as_ref(), member.key().as_ref()],
+        bump = member_membership.bump,
+        constraint = member_membership.is_active && member_membership.is_admin() @ VotingSystemError::NotCouncilMember
+    )]
+    pub member_membership: Account<'info, Membership>,
+    
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCouncilAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"pending_action", council.key().as_ref(), &pending_action.nonce.to_le_bytes()],
+        bump = pending_action.bump,
+        close = proposer
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    #[account(
+        mut,
+        constraint = community.council == Some(council.key()) @ VotingSystemError::NotCouncilMember
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    /// CHECK: Recibe el rent del pending action, solo se valida la dirección
+    #[account(
+        mut,
+        address = pending_action.proposer @ VotingSystemError::InvalidUser
+    )]
+    pub proposer: UncheckedAccount<'info>,
+    
+    /// CHECK: Solo para WithdrawFees (recipient aprobado) y Archive (authority); se valida la dirección
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+    
+    // Solo para ReplaceMember; membership del nuevo miembro
+    pub new_member_membership: Option<Account<'info, Membership>>,
+    
+    pub executor: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct RemoveStaleCouncilMember<'info> {
+    #[account(
+        mut,
+        seeds = [b"council", community.key().as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    pub community: Account<'info, Community>,
+    
+    /// CHECK: Membership PDA del miembro; puede estar cerrada, el handler lo comprueba
+    #[account(
+        seeds = [b"membership", community.key().as_ref(), member.as_ref()],
+        bump
+    )]
+    pub member_membership: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredCouncilAction<'info> {
+    #[account(
+        mut,
+        close = proposer
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    /// CHECK: Recibe el rent del pending action, solo se valida la dirección
+    #[account(
+        mut,
+        address = pending_action.proposer @ VotingSystemError::InvalidUser
+    )]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ArchiveCommunity<'info> {
+    #[account(
+        mut,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = !community.is_archived() @ VotingSystemError::CommunityArchived
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMembership<'info> {
+    #[account(
+        mut,
+        constraint = community.is_archived() @ VotingSystemError::CommunityNotArchived
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), membership.user.as_ref()],
+        bump = membership.bump,
+        close = member
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    /// CHECK: Recibe el rent de la membership, solo se valida la dirección
+    #[account(
+        mut,
+        address = membership.user @ VotingSystemError::InvalidUser
+    )]
+    pub member: UncheckedAccount<'info>,
+    
+    // El propio miembro o la authority haciendo limpieza
+    #[account(
+        constraint = closer.key() == membership.user ||
+                     closer.key() == community.authority @ VotingSystemError::InsufficientPermissions
+    )]
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCommunityLeaderboard<'info> {
+    #[account(
+        mut,
+        seeds = [b"community_leaderboard", community.key().as_ref()],
+        bump = community_leaderboard.bump,
+        close = authority
+    )]
+    pub community_leaderboard: Account<'info, CommunityLeaderboard>,
+    
+    #[account(
+        constraint = community.is_archived() @ VotingSystemError::CommunityNotArchived,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCommunityProfile<'info> {
+    #[account(
+        mut,
+        seeds = [b"community_profile", community.key().as_ref()],
+        bump = profile.bump,
+        close = authority
+    )]
+    pub profile: Account<'info, CommunityProfile>,
+    
+    #[account(
+        constraint = community.is_archived() @ VotingSystemError::CommunityNotArchived,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCustomCategory<'info> {
+    #[account(
+        mut,
+        seeds = [b"custom_category", community.key().as_ref(), custom_category.name.as_bytes()],
+        bump = custom_category.bump,
+        close = creator
+    )]
+    pub custom_category: Account<'info, CustomCategory>,
+    
+    #[account(
+        constraint = community.is_archived() @ VotingSystemError::CommunityNotArchived,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub community: Account<'info, Community>,
+    
+    /// CHECK: Admin que pagó la categoría, solo se valida la dirección
+    #[account(
+        mut,
+        address = custom_category.created_by @ VotingSystemError::InvalidUser
+    )]
+    pub creator: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCommunity<'info> {
+    #[account(
+        mut,
+        constraint = community.is_archived() @ VotingSystemError::CommunityNotArchived,
+        constraint = community.authority == authority.key() @ VotingSystemError::InsufficientPermissions,
+        close = authority
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Requerida si la comunidad es una sub-comunidad
+    #[account(mut)]
+    pub parent: Option<Account<'info, Community>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateCommunityRole<'info> {
+    #[account(
+        init,
+        seeds = [b"community_role", community.key().as_ref(), name.as_bytes()],
+        bump,
+        space = 8 + CommunityRole::LEN,
+        payer = admin
+    )]
+    pub role: Account<'info, CommunityRole>,
+    
+    #[account(
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncParentRules<'info> {
+    #[account(
+        mut,
+        constraint = community.parent == Some(parent.key()) @ VotingSystemError::InvalidParentCommunity
+    )]
+    pub community: Account<'info, Community>,
+    
+    pub parent: Account<'info, Community>,
+    
+    // Admin de la sub-comunidad o de la padre
+    #[account(
+        constraint = can_administer(&community, &admin_membership) @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCommunityRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"community_role", community.key().as_ref(), role.name.as_bytes()],
+        bump = role.bump
+    )]
+    pub role: Account<'info, CommunityRole>,
+    
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteCommunityRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"community_role", community.key().as_ref(), role.name.as_bytes()],
+        bump = role.bump,
+        close = admin
+    )]
+    pub role: Account<'info, CommunityRole>,
+    
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AssignCommunityRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), membership.user.as_ref()],
+        bump = membership.bump,
+        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        constraint = role.community == community.key() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub role: Option<Account<'info, CommunityRole>>,
+    
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVoting<'info> {
+    #[account(
+        init,
+        seeds = [b"vote", community.key().as_ref(), creator.key().as_ref()],
+        bump,
+        space = 8 + Vote::LEN,
+        payer = creator
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        mut,
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), creator.key().as_ref()],
+        bump = membership.bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        constraint = user.wallet == creator.key() @ VotingSystemError::InvalidUser
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        constraint = creator.lamports() >= 10_000_000 @ VotingSystemError::InsufficientFunds
+    )]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_pool"],
+        bump,
+        constraint = fee_pool.key() != Pubkey::default()
+    )]
+    pub fee_pool: Option<Account<'info, FeePool>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateKnowledgeVoting<'info> {
+    #[account(
+        init,
+        seeds = [b"knowledge-vote", community.key().as_ref(), creator.key().as_ref()],
+        bump,
+        space = 8 + Vote::LEN,
+        payer = creator
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        mut,
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership", community.key().as_ref(), creator.key().as_ref()],
+        bump = membership.bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        mut,
+        constraint = creator.lamports() >= 10_000_000 @ VotingSystemError::InsufficientFunds
+    )]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AssignModerator<'info> {
+    #[account(
+        mut,
+        constraint = membership.community == community.key() @ VotingSystemError::InvalidCommunity
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions,
+        constraint = can_administer(&community, &admin_membership) @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(
+        init,
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
+        bump,
+        space = 8 + ModerationLog::LEN,
+        payer = admin
+    )]
+    pub moderation_log: Account<'info, ModerationLog>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveModerator<'info> {
+    #[account(
+        mut,
+        constraint = membership.community == community.key() @ VotingSystemError::InvalidCommunity
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions,
+        constraint = can_administer(&community, &admin_membership) @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(
+        init,
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
+        bump,
+        space = 8 + ModerationLog::LEN,
+        payer = admin
+    )]
+    pub moderation_log: Account<'info, ModerationLog>,
     
-    // Sistema de tiers basado en reputación
-    let reward_multiplier = if reputation_points >= 5000 {
-        10 // VIP: 10% del pool
-    } else if reputation_points >= 1000 {
-        5  // Premium: 5% del pool
-    } else if reputation_points >= 500 {
-        3  // Advanced: 3% del pool
-    } else {
-        1  // Basic: 1% del pool
-    };
+    #[account(mut)]
+    pub admin: Signer<'info>,
     
-    // Calcular reward como porcentaje del pool diario
-    (total_distribution * reward_multiplier) / 100
+    pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct Initialize {}
+// ===================================================================
+// TAREAS 2.4.3-2.4.6: SISTEMA COMMIT-REVEAL + VALIDACIÓN COMUNITARIA
+// ===================================================================
 
+// Context para close_knowledge_voting (2.4.3)
 #[derive(Accounts)]
-pub struct InitializeFeePool<'info> {
+pub struct CloseKnowledgeVoting<'info> {
     #[account(
-        init,
-        seeds = [b"fee_pool"],
-        bump,
-        space = 8 + FeePool::LEN,
-        payer = authority
+        mut,
+        constraint = vote.status == VoteStatus::Active @ VotingSystemError::VoteNotActive,
+        constraint = vote.community == community.key() @ VotingSystemError::InvalidCommunity
     )]
-    pub fee_pool: Account<'info, FeePool>,
+    pub vote: Account<'info, Vote>,
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    pub community: Account<'info, Community>,
+}
+
+// Context para fail_unrevealed_vote
+#[derive(Accounts)]
+pub struct FailUnrevealedVote<'info> {
+    #[account(
+        mut,
+        constraint = vote.status == VoteStatus::AwaitingReveal @ VotingSystemError::NotAwaitingReveal
+    )]
+    pub vote: Account<'info, Vote>,
     
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        constraint = creator_user.wallet == vote.creator @ VotingSystemError::InvalidUser
+    )]
+    pub creator_user: Account<'info, User>,
 }
 
+// Context para claim_knowledge_reward (2.5.4)
 #[derive(Accounts)]
-pub struct CreateUser<'info> {
+pub struct ClaimKnowledgeReward<'info> {
     #[account(
-        init,
-        seeds = [b"user", wallet.key().as_ref()],
-        bump,
-        space = 8 + User::LEN,
-        payer = wallet
+        mut,
+        seeds = [b"participation", vote.key().as_ref(), user.wallet.as_ref()],
+        bump = participation.bump
+    )]
+    pub participation: Account<'info, Participation>,
+    
+    #[account(
+        constraint = vote.status == VoteStatus::Completed @ VotingSystemError::VoteNotCompleted
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        mut,
+        constraint = user.wallet == claimer.key() @ VotingSystemError::InvalidUser
     )]
     pub user: Account<'info, User>,
     
+    pub claimer: Signer<'info>,
+}
+
+// Context para close_vote
+#[derive(Accounts)]
+pub struct CloseVote<'info> {
+    #[account(
+        mut,
+        close = creator,
+        constraint = vote.creator == creator.key() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub vote: Account<'info, Vote>,
+    
     #[account(mut)]
-    pub wallet: Signer<'info>,
+    pub creator: Signer<'info>,
+}
+
+// Context para reveal_correct_answer (2.4.4)
+#[derive(Accounts)]
+pub struct RevealAnswer<'info> {
+    #[account(
+        mut,
+        constraint = vote.status == VoteStatus::AwaitingReveal @ VotingSystemError::NotAwaitingReveal
+    )]
+    pub vote: Account<'info, Vote>,
     
-    pub system_program: Program<'info, System>,
+    #[account(
+        constraint = creator.key() == vote.creator @ VotingSystemError::InsufficientPermissions
+    )]
+    pub creator: Signer<'info>,
 }
 
+// Context para vote_confidence (2.4.5)
 #[derive(Accounts)]
-#[instruction(option_selected: u8)]
-pub struct CastVote<'info> {
+pub struct VoteConfidence<'info> {
     #[account(
         init,
-        seeds = [b"participation", vote.key().as_ref(), voter.key().as_ref()],
+        seeds = [b"confidence", vote.key().as_ref(), user.wallet.as_ref()],
         bump,
-        space = 8 + Participation::LEN,
+        space = 8 + ConfidenceParticipation::LEN,
         payer = voter
     )]
-    pub participation: Account<'info, Participation>,
+    pub confidence_participation: Account<'info, ConfidenceParticipation>,
     
     #[account(
         mut,
-        constraint = vote.status == VoteStatus::Active @ VotingSystemError::VoteNotActive
+        constraint = vote.status == VoteStatus::ConfidenceVoting @ VotingSystemError::NotInConfidencePhase
     )]
     pub vote: Account<'info, Vote>,
     
+    // Solo pueden votar confianza quienes respondieron la pregunta
     #[account(
-        constraint = membership.community == vote.community @ VotingSystemError::NotCommunityMember,
-        constraint = membership.user == voter.key() @ VotingSystemError::NotCommunityMember,
-        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
+        seeds = [b"participation", vote.key().as_ref(), user.wallet.as_ref()],
+        bump = participation.bump
     )]
-    pub membership: Account<'info, Membership>,
+    pub participation: Account<'info, Participation>,
+    
+    // user.wallet == voter o session válida: se verifica en authorize_voter
+    #[account(mut)]
+    pub user: Account<'info, User>,
     
     #[account(
-        mut,
-        constraint = user.wallet == voter.key() @ VotingSystemError::InvalidUser
+        constraint = membership.user == user.wallet @ VotingSystemError::NotCommunityMember,
+        constraint = membership.community == vote.community @ VotingSystemError::InvalidCommunity,
+        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
     )]
-    pub user: Account<'info, User>,
+    pub membership: Account<'info, Membership>,
     
+    // Wallet del usuario o session key efímera
     #[account(mut)]
     pub voter: Signer<'info>,
     
+    #[account(
+        mut,
+        seeds = [b"session", vote.community.as_ref(), user.wallet.as_ref(), voter.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+    
     pub system_program: Program<'info, System>,
 }
 
+// Context para finalize_confidence_voting (2.4.6)
 #[derive(Accounts)]
-pub struct JoinCommunity<'info> {
+pub struct FinalizeConfidenceVoting<'info> {
+    #[account(
+        mut,
+        constraint = vote.status == VoteStatus::ConfidenceVoting @ VotingSystemError::NotInConfidencePhase
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        mut,
+        constraint = creator_user.wallet == vote.creator @ VotingSystemError::InvalidUser
+    )]
+    pub creator_user: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct OpenAnswerDispute<'info> {
     #[account(
         init,
-        seeds = [b"membership", community.key().as_ref(), member.key().as_ref()],
+        seeds = [b"dispute", vote.key().as_ref()],
         bump,
-        space = 8 + Membership::LEN,
-        payer = member
+        space = 8 + AnswerDispute::LEN,
+        payer = challenger
     )]
-    pub membership: Account<'info, Membership>,
+    pub dispute: Account<'info, AnswerDispute>,
     
     #[account(
         mut,
-        constraint = community.is_active @ VotingSystemError::CommunityInactive
+        constraint = vote.vote_type == VoteType::Knowledge @ VotingSystemError::InvalidVoteType
     )]
-    pub community: Account<'info, Community>,
+    pub vote: Account<'info, Vote>,
     
+    // Solo quienes respondieron la pregunta pueden impugnarla
     #[account(
-        constraint = user.wallet == member.key() @ VotingSystemError::InvalidUser
+        seeds = [b"participation", vote.key().as_ref(), challenger.key().as_ref()],
+        bump = participation.bump
     )]
-    pub user: Account<'info, User>,
+    pub participation: Account<'info, Participation>,
     
     #[account(mut)]
-    pub member: Signer<'info>,
+    pub challenger: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(name: String, category: u8, quorum_percentage: u8)]
-pub struct CreateCommunity<'info> {
+pub struct RespondToDispute<'info> {
     #[account(
-        init,
-        seeds = [b"community", authority.key().as_ref(), name.as_bytes()],
-        bump,
-        space = 8 + Community::LEN,
-        payer = authority
+        mut,
+        seeds = [b"dispute", dispute.vote.as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.creator == creator.key() @ VotingSystemError::InsufficientPermissions
     )]
-    pub community: Account<'info, Community>,
+    pub dispute: Account<'info, AnswerDispute>,
     
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub creator: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CreateVoting<'info> {
+pub struct ResolveDispute<'info> {
     #[account(
-        init,
-        seeds = [b"vote", community.key().as_ref(), creator.key().as_ref()],
-        bump,
-        space = 8 + Vote::LEN,
-        payer = creator
+        mut,
+        seeds = [b"dispute", vote.key().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Account<'info, AnswerDispute>,
+    
+    #[account(
+        mut,
+        constraint = vote.status == VoteStatus::Disputed @ VotingSystemError::DisputeNotOpen
     )]
     pub vote: Account<'info, Vote>,
     
     #[account(
         mut,
-        constraint = community.is_active @ VotingSystemError::CommunityInactive
+        constraint = creator_user.wallet == vote.creator @ VotingSystemError::InvalidUser
     )]
-    pub community: Account<'info, Community>,
+    pub creator_user: Account<'info, User>,
     
+    /// CHECK: Recibe las fianzas si gana, solo se valida la dirección
     #[account(
-        constraint = user.wallet == creator.key() @ VotingSystemError::InvalidUser
+        mut,
+        address = dispute.creator @ VotingSystemError::InvalidUser
     )]
-    pub user: Account<'info, User>,
+    pub creator: UncheckedAccount<'info>,
     
+    /// CHECK: Recibe las fianzas si gana, solo se valida la dirección
     #[account(
         mut,
-        constraint = creator.lamports() >= 10_000_000 @ VotingSystemError::InsufficientFunds
+        address = dispute.challenger @ VotingSystemError::InvalidUser
     )]
-    pub creator: Signer<'info>,
+    pub challenger: UncheckedAccount<'info>,
+    
+    #[account(
+        constraint = moderator_membership.community == vote.community @ VotingSystemError::InvalidCommunity,
+        constraint = moderator_membership.user == moderator.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = moderator_membership.is_active @ VotingSystemError::NotCommunityMember,
+        constraint = moderator_membership.is_moderator() @ VotingSystemError::InsufficientPermissions
+    )]
+    pub moderator_membership: Account<'info, Membership>,
     
+    pub moderator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAnswerDispute<'info> {
     #[account(
         mut,
-        seeds = [b"fee_pool"],
-        bump,
-        constraint = fee_pool.key() != Pubkey::default()
+        close = challenger,
+        seeds = [b"dispute", dispute.vote.as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.status != DisputeStatus::Open @ VotingSystemError::DisputeStillOpen,
+        constraint = dispute.challenger == challenger.key() @ VotingSystemError::InsufficientPermissions
     )]
-    pub fee_pool: Option<Account<'info, FeePool>>,
+    pub dispute: Account<'info, AnswerDispute>,
     
-    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
 }
 
+// ===================================================================
+// CONTEXT STRUCTS PARA TORNEOS
+// ===================================================================
+
 #[derive(Accounts)]
-pub struct CreateKnowledgeVoting<'info> {
+#[instruction(name: String)]
+pub struct CreateTournament<'info> {
     #[account(
         init,
-        seeds = [b"knowledge-vote", community.key().as_ref(), creator.key().as_ref()],
+        seeds = [b"tournament", community.key().as_ref(), creator.key().as_ref(), name.as_bytes()],
         bump,
-        space = 8 + Vote::LEN,
+        space = 8 + Tournament::LEN,
         payer = creator
     )]
-    pub vote: Account<'info, Vote>,
+    pub tournament: Account<'info, Tournament>,
     
     #[account(
-        mut,
         constraint = community.is_active @ VotingSystemError::CommunityInactive
     )]
     pub community: Account<'info, Community>,
     
     #[account(
-        mut,
-        constraint = creator.lamports() >= 10_000_000 @ VotingSystemError::InsufficientFunds
+        constraint = membership.community == community.key() @ VotingSystemError::NotCommunityMember,
+        constraint = membership.user == creator.key() @ VotingSystemError::NotCommunityMember,
+        constraint = membership.is_active @ VotingSystemError::NotCommunityMember
     )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(mut)]
     pub creator: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct AssignModerator<'info> {
-    #[account(
-        mut,
-        constraint = membership.community == admin_membership.community @ VotingSystemError::InvalidCommunity
-    )]
-    pub membership: Account<'info, Membership>,
-    
-    #[account(
-        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions
-    )]
-    pub admin_membership: Account<'info, Membership>,
-    
+pub struct AddTournamentRound<'info> {
     #[account(
         init,
-        seeds = [b"moderation_log", membership.community.as_ref(), admin_membership.user.as_ref()],
+        seeds = [b"tournament_round", tournament.key().as_ref(), &[tournament.rounds.len() as u8]],
         bump,
-        space = 8 + ModerationLog::LEN,
-        payer = admin
+        space = 8 + Vote::LEN,
+        payer = creator
     )]
-    pub moderation_log: Account<'info, ModerationLog>,
-    
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    pub vote: Account<'info, Vote>,
     
-    pub system_program: Program<'info, System>,
-}
-
-// ===================================================================
-// TAREAS 2.4.3-2.4.6: SISTEMA COMMIT-REVEAL + VALIDACIÓN COMUNITARIA
-// ===================================================================
-
-// Context para reveal_correct_answer (2.4.4)
-#[derive(Accounts)]
-pub struct RevealAnswer<'info> {
     #[account(
         mut,
-        constraint = vote.status == VoteStatus::Completed @ VotingSystemError::VoteNotCompleted
+        constraint = tournament.creator == creator.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = tournament.community == community.key() @ VotingSystemError::InvalidCommunity
     )]
-    pub vote: Account<'info, Vote>,
+    pub tournament: Account<'info, Tournament>,
     
     #[account(
-        constraint = creator.key() == vote.creator @ VotingSystemError::InsufficientPermissions
+        mut,
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
     )]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
     pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
-// Context para vote_confidence (2.4.5)
 #[derive(Accounts)]
-pub struct VoteConfidence<'info> {
+pub struct JoinTournament<'info> {
     #[account(
-        mut,
-        constraint = vote.status == VoteStatus::ConfidenceVoting @ VotingSystemError::NotInConfidencePhase
+        init,
+        seeds = [b"tournament_score", tournament.key().as_ref(), participant.key().as_ref()],
+        bump,
+        space = 8 + TournamentScore::LEN,
+        payer = participant
     )]
-    pub vote: Account<'info, Vote>,
+    pub score: Account<'info, TournamentScore>,
     
-    #[account(
-        mut,
-        constraint = user.wallet == voter.key() @ VotingSystemError::InvalidUser
-    )]
-    pub user: Account<'info, User>,
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
     
-    #[account(
-        constraint = membership.user == user.wallet @ VotingSystemError::NotCommunityMember,
-        constraint = membership.community == vote.community @ VotingSystemError::InvalidCommunity,
+    #[account(
+        constraint = membership.community == tournament.community @ VotingSystemError::NotCommunityMember,
+        constraint = membership.user == participant.key() @ VotingSystemError::NotCommunityMember,
         constraint = membership.is_active @ VotingSystemError::NotCommunityMember
     )]
     pub membership: Account<'info, Membership>,
     
-    pub voter: Signer<'info>,
+    #[account(mut)]
+    pub participant: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
-// Context para finalize_confidence_voting (2.4.6)
 #[derive(Accounts)]
-pub struct FinalizeConfidenceVoting<'info> {
+pub struct RecordTournamentRound<'info> {
     #[account(
         mut,
-        constraint = vote.status == VoteStatus::ConfidenceVoting @ VotingSystemError::NotInConfidencePhase
+        seeds = [b"tournament_score", tournament.key().as_ref(), score.user.as_ref()],
+        bump = score.bump
     )]
+    pub score: Account<'info, TournamentScore>,
+    
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
     pub vote: Account<'info, Vote>,
     
+    #[account(
+        seeds = [b"participation", vote.key().as_ref(), score.user.as_ref()],
+        bump = participation.bump
+    )]
+    pub participation: Account<'info, Participation>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTournament<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    /// CHECK: Recibe el premio no repartido, solo se valida la dirección
     #[account(
         mut,
-        constraint = creator_user.wallet == vote.creator @ VotingSystemError::InvalidUser
+        address = tournament.creator @ VotingSystemError::InvalidUser
     )]
-    pub creator_user: Account<'info, User>,
+    pub creator: UncheckedAccount<'info>,
 }
 
 // ===================================================================
@@ -1652,289 +5859,6 @@ pub struct UpdateCommunityLeaderboard<'info> {
     pub membership: Account<'info, Membership>,
 }
 
-// TAREA 2.4.4: Función de revelación de respuesta
-pub fn reveal_correct_answer(
-    ctx: Context<RevealAnswer>,
-    answer: String,
-) -> Result<()> {
-    let vote = &mut ctx.accounts.vote;
-    let clock = Clock::get()?;
-    
-    // Validar que es una pregunta de Knowledge
-    require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
-    
-    // Validar que es el creator
-    require!(vote.creator == ctx.accounts.creator.key(), VotingSystemError::InsufficientPermissions);
-    
-    // Validar que la votación ha terminado
-    require!(vote.status == VoteStatus::Completed, VotingSystemError::VoteNotCompleted);
-    
-    // Validar que hay hash de respuesta para verificar
-    let stored_hash = vote.answer_hash.ok_or(VotingSystemError::NoAnswerHashStored)?;
-    
-    // Verificar que el hash coincide
-    let answer_hash = hash(answer.as_bytes()).to_bytes();
-    require!(stored_hash == answer_hash, VotingSystemError::InvalidAnswerHash);
-    
-    // Validar deadline de revelación
-    if let Some(deadline) = vote.reveal_deadline {
-        require!(clock.unix_timestamp <= deadline, VotingSystemError::RevealDeadlineExpired);
-    }
-    
-    // Revelar respuesta e iniciar fase de confianza
-    vote.revealed_answer = Some(answer.clone());
-    vote.status = VoteStatus::ConfidenceVoting;
-    vote.confidence_deadline = Some(clock.unix_timestamp + 86400); // 24 horas
-    
-    msg!("✨ Respuesta revelada para pregunta Knowledge!");
-    msg!("Pregunta: {}", vote.question);
-    msg!("Respuesta correcta: {}", answer);
-    msg!("Fase de confianza iniciada por 24h");
-    
-    Ok(())
-}
-
-// ===================================================================
-// TAREA 2.6.4: SISTEMA DE LEADERBOARDS - ACTUALIZACIÓN AUTOMÁTICA
-// ===================================================================
-
-// TAREA 2.6.1: Inicializar GlobalLeaderboard
-pub fn initialize_global_leaderboard(
-    ctx: Context<InitializeGlobalLeaderboard>,
-) -> Result<()> {
-    let leaderboard = &mut ctx.accounts.global_leaderboard;
-    let clock = Clock::get()?;
-    
-    leaderboard.top_users = Vec::new();
-    leaderboard.last_updated = clock.unix_timestamp;
-    leaderboard.total_users = 0;
-    leaderboard.total_reputation = 0;
-    leaderboard.update_authority = ctx.accounts.authority.key();
-    leaderboard.bump = ctx.bumps.global_leaderboard;
-    
-    msg!("🏆 Global Leaderboard initialized successfully!");
-    msg!("Authority: {}", leaderboard.update_authority);
-    
-    Ok(())
-}
-
-// TAREA 2.6.2: Inicializar CommunityLeaderboard
-pub fn initialize_community_leaderboard(
-    ctx: Context<InitializeCommunityLeaderboard>,
-) -> Result<()> {
-    let leaderboard = &mut ctx.accounts.community_leaderboard;
-    let community = &ctx.accounts.community;
-    let clock = Clock::get()?;
-    
-    leaderboard.community = community.key();
-    leaderboard.top_users = Vec::new();
-    leaderboard.last_updated = clock.unix_timestamp;
-    leaderboard.total_votes_cast = 0;
-    leaderboard.total_votations_created = 0;
-    leaderboard.most_active_user = Pubkey::default();
-    leaderboard.bump = ctx.bumps.community_leaderboard;
-    
-    msg!("🏆 Community Leaderboard initialized for: {}", community.name);
-    
-    Ok(())
-}
-
-// TAREA 2.6.4: Actualizar GlobalLeaderboard con usuario
-pub fn update_global_leaderboard(
-    ctx: Context<UpdateGlobalLeaderboard>,
-) -> Result<()> {
-    let leaderboard = &mut ctx.accounts.global_leaderboard;
-    let user = &ctx.accounts.user;
-    let clock = Clock::get()?;
-    
-    // Validar autoridad
-    require!(
-        ctx.accounts.authority.key() == leaderboard.update_authority,
-        VotingSystemError::InsufficientPermissions
-    );
-    
-    // Crear entrada del usuario
-    let entry = LeaderboardEntry::from_user_data(
-        user.wallet,
-        user.reputation_points,
-        user.level,
-        user.voting_weight,
-        user.total_votes_cast,
-        0, // total_votations_created - se calcularía desde otras fuentes
-    );
-    
-    // Actualizar ranking
-    leaderboard.update_user_ranking(user.wallet, entry);
-    leaderboard.last_updated = clock.unix_timestamp;
-    leaderboard.total_users += 1;
-    leaderboard.total_reputation += user.reputation_points;
-    
-    msg!("📈 Global leaderboard updated!");
-    msg!("User: {}", user.wallet);
-    msg!("Reputation: {} points, Level: {}, Weight: {}x", 
-         user.reputation_points, user.level, user.voting_weight);
-    
-    // Mostrar top 3 current
-    for (i, entry) in leaderboard.top_users.iter().take(3).enumerate() {
-        msg!("  #{}: {} ({} pts)", i + 1, entry.user, entry.reputation_points);
-    }
-    
-    Ok(())
-}
-
-// TAREA 2.6.4: Actualizar CommunityLeaderboard con usuario
-pub fn update_community_leaderboard(
-    ctx: Context<UpdateCommunityLeaderboard>,
-) -> Result<()> {
-    let leaderboard = &mut ctx.accounts.community_leaderboard;
-    let user = &ctx.accounts.user;
-    let membership = &ctx.accounts.membership;
-    let community = &ctx.accounts.community;
-    let clock = Clock::get()?;
-    
-    // Validar que el usuario es miembro de la comunidad
-    require!(membership.community == community.key(), VotingSystemError::InvalidCommunity);
-    require!(membership.user == user.wallet, VotingSystemError::InvalidUser);
-    require!(membership.is_active, VotingSystemError::NotCommunityMember);
-    
-    // Crear entrada del usuario (priorizando actividad en la comunidad)
-    let entry = LeaderboardEntry::from_user_data(
-        user.wallet,
-        user.reputation_points,
-        user.level,
-        user.voting_weight,
-        user.total_votes_cast,
-        0, // Se puede añadir conteo de votaciones creadas en esta comunidad
-    );
-    
-    // Actualizar ranking comunitario
-    leaderboard.update_user_ranking(user.wallet, entry);
-    leaderboard.last_updated = clock.unix_timestamp;
-    leaderboard.total_votes_cast = community.total_votes;
-    
-    msg!("🏚️ Community leaderboard updated!");
-    msg!("Community: {}", community.name);
-    msg!("User: {}", user.wallet);
-    msg!("Votes cast: {}, Reputation: {}", user.total_votes_cast, user.reputation_points);
-    
-    // Mostrar top 3 de la comunidad
-    for (i, entry) in leaderboard.top_users.iter().enumerate() {
-        msg!("  #{}: {} ({} votes)", i + 1, entry.user, entry.total_votes_cast);
-    }
-    
-    Ok(())
-}
-
-// TAREA 2.4.5: Votación de confianza
-pub fn vote_confidence(
-    ctx: Context<VoteConfidence>,
-    is_confident: bool,
-) -> Result<()> {
-    let vote = &mut ctx.accounts.vote;
-    let user = &mut ctx.accounts.user;
-    let membership = &ctx.accounts.membership;
-    let clock = Clock::get()?;
-    
-    // Validar que es una pregunta de Knowledge
-    require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
-    
-    // Validar que está en fase de confianza
-    require!(vote.status == VoteStatus::ConfidenceVoting, VotingSystemError::NotInConfidencePhase);
-    
-    // Validar que el usuario es miembro activo
-    require!(membership.is_active, VotingSystemError::NotCommunityMember);
-    require!(membership.user == user.wallet, VotingSystemError::InvalidUser);
-    
-    // Validar deadline de confianza
-    if let Some(deadline) = vote.confidence_deadline {
-        require!(clock.unix_timestamp <= deadline, VotingSystemError::ConfidenceDeadlineExpired);
-    }
-    
-    // TAREA 2.5.5: Puntos de confianza (+/-2)
-    if is_confident {
-        user.reputation_points += 2;
-        vote.confidence_votes_for += 1;
-        msg!("📈 +2 reputación por voto de confianza A FAVOR");
-    } else {
-        if user.reputation_points >= 2 {
-            user.reputation_points -= 2;
-        }
-        vote.confidence_votes_against += 1;
-        msg!("📉 -2 reputación por voto de confianza EN CONTRA");
-    }
-    
-    // Actualizar nivel si es necesario
-    let new_level = (user.reputation_points / 10) + 1;
-    if new_level as u32 > user.level {
-        user.level = new_level as u32;
-        msg!("🎆 ¡Nuevo nivel alcanzado: {}!", user.level);
-    }
-    
-    msg!("🗺️ Voto de confianza registrado!");
-    msg!("Usuario: {}", user.wallet);
-    msg!("Confianza: {}", if is_confident { "A favor" } else { "En contra" });
-    msg!("Reputación total: {}", user.reputation_points);
-    msg!("Votos a favor: {}, En contra: {}", vote.confidence_votes_for, vote.confidence_votes_against);
-    
-    Ok(())
-}
-
-// TAREA 2.4.6: Validación comunitaria final
-pub fn finalize_confidence_voting(
-    ctx: Context<FinalizeConfidenceVoting>,
-) -> Result<()> {
-    let vote = &mut ctx.accounts.vote;
-    let creator_user = &mut ctx.accounts.creator_user;
-    let clock = Clock::get()?;
-    
-    // Validar que es una pregunta de Knowledge
-    require!(vote.vote_type == VoteType::Knowledge, VotingSystemError::InvalidVoteType);
-    
-    // Validar que está en fase de confianza
-    require!(vote.status == VoteStatus::ConfidenceVoting, VotingSystemError::NotInConfidencePhase);
-    
-    // Validar que ha pasado el deadline
-    if let Some(deadline) = vote.confidence_deadline {
-        require!(clock.unix_timestamp > deadline, VotingSystemError::ConfidenceVotingStillActive);
-    }
-    
-    // Calcular resultado de validación comunitaria
-    let total_confidence_votes = vote.confidence_votes_for + vote.confidence_votes_against;
-    let confidence_threshold = (total_confidence_votes * 60) / 100; // 60% threshold
-    
-    let is_answer_validated = vote.confidence_votes_for >= confidence_threshold;
-    
-    // Finalizar votación
-    vote.status = VoteStatus::Completed;
-    
-    // Impacto en reputación del creator
-    if is_answer_validated {
-        // Respuesta validada - bonus de reputación
-        creator_user.reputation_points += 10;
-        msg!("✅ Respuesta validada por la comunidad - Creator +10 reputación");
-    } else {
-        // Respuesta cuestionada - penalty
-        if creator_user.reputation_points >= 5 {
-            creator_user.reputation_points -= 5;
-        }
-        msg!("❌ Respuesta cuestionada por la comunidad - Creator -5 reputación");
-    }
-    
-    // Actualizar nivel si es necesario
-    let new_level = (creator_user.reputation_points / 10) + 1;
-    if new_level as u32 > creator_user.level {
-        creator_user.level = new_level as u32;
-        msg!("🎆 ¡Nuevo nivel alcanzado: {}!", creator_user.level);
-    }
-    
-    msg!("🏁 Validación comunitaria finalizada!");
-    msg!("Pregunta: {}", vote.question);
-    msg!("Votos confianza - A favor: {}, En contra: {}", vote.confidence_votes_for, vote.confidence_votes_against);
-    msg!("Resultado: {}", if is_answer_validated { "Validada" } else { "Cuestionada" });
-    
-    Ok(())
-}
-
 // === ESTRUCTURAS CONTEXT PARA SISTEMA DE CATEGORÍAS ===
 
 #[derive(Accounts)]
@@ -1952,11 +5876,18 @@ pub struct CreateCustomCategory<'info> {
     pub community: Account<'info, Community>,
     
     #[account(
-        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions,
-        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity
+        mut,
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions
     )]
     pub admin_membership: Account<'info, Membership>,
     
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = admin_membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
     #[account(mut)]
     pub admin: Signer<'info>,
     
@@ -2030,9 +5961,15 @@ pub struct ForceCloseVote<'info> {
     )]
     pub moderator_membership: Account<'info, Membership>,
     
+    #[account(
+        mut,
+        constraint = community.key() == vote.community @ VotingSystemError::InvalidCommunity
+    )]
+    pub community: Account<'info, Community>,
+    
     #[account(
         init,
-        seeds = [b"moderation_log", vote.community.as_ref(), moderator_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = moderator
@@ -2045,6 +5982,66 @@ pub struct ForceCloseVote<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct CancelVote<'info> {
+    #[account(
+        mut,
+        constraint = vote.creator == creator.key() @ VotingSystemError::InsufficientPermissions,
+        constraint = vote.community == community.key() @ VotingSystemError::InvalidCommunity
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
+    #[account(
+        mut,
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_pool"],
+        bump
+    )]
+    pub fee_pool: Option<Account<'info, FeePool>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimCancelledBallot<'info> {
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"participation", vote.key().as_ref(), wallet.key().as_ref()],
+        bump = participation.bump
+    )]
+    pub participation: Account<'info, Participation>,
+    
+    #[account(
+        mut,
+        constraint = vote.has_pending_ballot_refunds() @ VotingSystemError::VoteNotCancelled
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", wallet.key().as_ref()],
+        bump = user.bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct BanUser<'info> {
     #[account(
@@ -2054,10 +6051,17 @@ pub struct BanUser<'info> {
     pub membership: Account<'info, Membership>,
     
     #[account(
-        constraint = moderator_membership.can_moderate() @ VotingSystemError::InsufficientPermissions
+        mut,
+        constraint = moderator_membership.user == moderator.key() @ VotingSystemError::InsufficientPermissions
     )]
     pub moderator_membership: Account<'info, Membership>,
     
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = moderator_membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
     #[account(
         init,
         seeds = [b"ban_record", membership.community.as_ref(), membership.user.as_ref()],
@@ -2067,9 +6071,15 @@ pub struct BanUser<'info> {
     )]
     pub ban_record: Account<'info, BanRecord>,
     
+    #[account(
+        mut,
+        constraint = community.key() == membership.community @ VotingSystemError::InvalidCommunity
+    )]
+    pub community: Account<'info, Community>,
+    
     #[account(
         init,
-        seeds = [b"moderation_log", membership.community.as_ref(), moderator_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = moderator
@@ -2095,9 +6105,15 @@ pub struct CloseVoting<'info> {
     )]
     pub moderator_membership: Account<'info, Membership>,
     
+    #[account(
+        mut,
+        constraint = community.key() == vote.community @ VotingSystemError::InvalidCommunity
+    )]
+    pub community: Account<'info, Community>,
+    
     #[account(
         init,
-        seeds = [b"moderation_log", vote.community.as_ref(), moderator_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = moderator
@@ -2112,18 +6128,29 @@ pub struct CloseVoting<'info> {
 
 #[derive(Accounts)]
 pub struct WithdrawFees<'info> {
+    pub community: Account<'info, Community>,
+    
+    // Vault de la comunidad: cuenta del system program que custodia los fees
     #[account(
         mut,
-        constraint = community.authority == admin_membership.user @ VotingSystemError::InsufficientPermissions
+        seeds = [b"community_vault", community.key().as_ref()],
+        bump
     )]
-    pub community: Account<'info, Community>,
+    pub vault: SystemAccount<'info>,
     
     #[account(
-        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions,
-        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity
+        mut,
+        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions
     )]
     pub admin_membership: Account<'info, Membership>,
     
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = admin_membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
     #[account(mut)]
     pub admin: Signer<'info>,
     
@@ -2188,7 +6215,10 @@ pub struct UpdateFeePool<'info> {
 
 #[derive(Accounts)]
 pub struct RemoveMember<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = membership.community == community.key() @ VotingSystemError::InvalidCommunity
+    )]
     pub membership: Account<'info, Membership>,
     
     #[account(mut)]
@@ -2196,13 +6226,13 @@ pub struct RemoveMember<'info> {
     
     #[account(
         constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions,
-        constraint = admin_membership.community == membership.community @ VotingSystemError::InvalidCommunity
+        constraint = can_administer(&community, &admin_membership) @ VotingSystemError::InvalidCommunity
     )]
     pub admin_membership: Account<'info, Membership>,
     
     #[account(
         init,
-        seeds = [b"moderation_log", membership.community.as_ref(), admin_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = admin
@@ -2258,13 +6288,27 @@ pub struct ReviewReport<'info> {
     pub report: Account<'info, Report>,
     
     #[account(
-        constraint = moderator_membership.can_moderate() @ VotingSystemError::InsufficientPermissions
+        mut,
+        constraint = moderator_membership.community == report.community @ VotingSystemError::InvalidCommunity,
+        constraint = moderator_membership.user == moderator.key() @ VotingSystemError::InsufficientPermissions
     )]
     pub moderator_membership: Account<'info, Membership>,
     
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = moderator_membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        mut,
+        constraint = community.key() == report.community @ VotingSystemError::InvalidCommunity
+    )]
+    pub community: Account<'info, Community>,
+    
     #[account(
         init,
-        seeds = [b"moderation_log", report.community.as_ref(), moderator_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = moderator
@@ -2317,9 +6361,15 @@ pub struct ReviewAppeal<'info> {
     )]
     pub admin_membership: Account<'info, Membership>,
     
+    #[account(
+        mut,
+        constraint = community.key() == appeal.community @ VotingSystemError::InvalidCommunity
+    )]
+    pub community: Account<'info, Community>,
+    
     #[account(
         init,
-        seeds = [b"moderation_log", appeal.community.as_ref(), admin_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = admin
@@ -2337,7 +6387,7 @@ pub struct ReviewAppeal<'info> {
 #[derive(Accounts)]
 pub struct RequestMembership<'info> {
     #[account(
-        init,
+        init_if_needed,
         seeds = [b"membership_request", community.key().as_ref(), requester.key().as_ref()],
         bump,
         space = 8 + MembershipRequest::LEN,
@@ -2361,6 +6411,21 @@ pub struct RequestMembership<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct CancelMembershipRequest<'info> {
+    #[account(
+        mut,
+        seeds = [b"membership_request", membership_request.community.as_ref(), requester.key().as_ref()],
+        bump = membership_request.bump,
+        constraint = membership_request.status == MembershipRequestStatus::Pending @ VotingSystemError::RequestNotPending,
+        close = requester
+    )]
+    pub membership_request: Account<'info, MembershipRequest>,
+    
+    #[account(mut)]
+    pub requester: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct ApproveMembership<'info> {
     #[account(
@@ -2370,7 +6435,7 @@ pub struct ApproveMembership<'info> {
     pub membership_request: Account<'info, MembershipRequest>,
     
     #[account(
-        init,
+        init_if_needed,
         seeds = [b"membership", community.key().as_ref(), membership_request.user.as_ref()],
         bump,
         space = 8 + Membership::LEN,
@@ -2378,18 +6443,28 @@ pub struct ApproveMembership<'info> {
     )]
     pub membership: Account<'info, Membership>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = community.is_active @ VotingSystemError::CommunityInactive
+    )]
     pub community: Account<'info, Community>,
     
     #[account(
-        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions,
-        constraint = admin_membership.community == community.key() @ VotingSystemError::InvalidCommunity
+        mut,
+        constraint = can_administer(&community, &admin_membership) @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions
     )]
     pub admin_membership: Account<'info, Membership>,
     
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = admin_membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
     #[account(
         init,
-        seeds = [b"moderation_log", community.key().as_ref(), admin_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = admin
@@ -2411,14 +6486,27 @@ pub struct RejectMembership<'info> {
     pub membership_request: Account<'info, MembershipRequest>,
     
     #[account(
-        constraint = admin_membership.is_admin() @ VotingSystemError::InsufficientPermissions,
-        constraint = admin_membership.community == membership_request.community @ VotingSystemError::InvalidCommunity
+        mut,
+        constraint = community.key() == membership_request.community @ VotingSystemError::InvalidCommunity
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        constraint = admin_membership.community == membership_request.community @ VotingSystemError::InvalidCommunity,
+        constraint = admin_membership.user == admin.key() @ VotingSystemError::InsufficientPermissions
     )]
     pub admin_membership: Account<'info, Membership>,
     
+    /// CHECK: CommunityRole asignado a la membership (opcional); si ya no existe, has_permission limpia la referencia
+    #[account(
+        address = admin_membership.custom_role.unwrap_or_default() @ VotingSystemError::InvalidCommunityRole
+    )]
+    pub custom_role: Option<UncheckedAccount<'info>>,
+    
     #[account(
         init,
-        seeds = [b"moderation_log", membership_request.community.as_ref(), admin_membership.user.as_ref()],
+        seeds = [b"moderation_log", community.key().as_ref(), &community.moderation_log_count.to_le_bytes()],
         bump,
         space = 8 + ModerationLog::LEN,
         payer = admin
@@ -1,33 +1,188 @@
 use anchor_lang::prelude::*;
+use crate::state::fees::CommunityTier;
+use crate::state::roles::DEFAULT_MEMBER_PERMISSIONS;
 
 #[account]
 pub struct Community {
     pub authority: Pubkey,          // Creador/admin de la comunidad
+    pub pending_authority: Option<Pubkey>, // Nueva authority propuesta (pendiente de aceptar)
+    pub admin_count: u8,            // Memberships con rol Admin (max 10)
     pub moderators: Vec<Pubkey>,    // Lista de moderadores (max 5)
     pub name: String,               // Nombre (max 50 chars)
     pub category: u8,               // Categoría (0-9)
     pub quorum_percentage: u8,      // % para quorum (1-100)
     pub total_members: u64,         // Contador de miembros
+    pub max_members: Option<u64>,   // Límite propio (None = solo el del tier)
+    pub waitlist_count: u16,        // Usuarios en la lista de espera (tienen prioridad)
     pub total_votes: u64,           // Contador de votaciones
-    pub fee_collected: u64,         // Total fees en lamports
     pub created_at: i64,            // Timestamp creación
     pub is_active: bool,            // Estado activo/inactivo
     pub requires_approval: bool,    // Requiere aprobación para unirse
+    pub tier: CommunityTier,        // Public | Private | Premium (pagado al crear)
+    pub cancel_penalty_percentage: u8, // % del fee retenido al cancelar una votación
+    pub membership_fee: u64,        // Cuota por periodo en lamports (va al vault)
+    pub membership_period: i64,     // Duración del periodo en segundos (0 = sin cuotas)
+    pub member_permissions: u16,    // Permisos de cualquier miembro (bitflags)
+    pub request_cooldown: i64,      // Espera tras un rechazo para volver a solicitar (segundos)
+    pub council: Option<Pubkey>,    // Consejo M-de-N para acciones sensibles (si existe)
+    pub archived_at: Option<i64>,   // Archivada: congelada y pendiente de cerrar
+    pub parent: Option<Pubkey>,     // Comunidad padre (sub-comunidad / capítulo)
+    pub child_count: u16,           // Sub-comunidades activas (max 16)
+    pub moderation_log_count: u64,  // ModerationLogs creados (nonce de sus seeds)
     pub bump: u8,                   // PDA bump
 }
 
 impl Community {
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
-        4 + (32 * 5) + // moderators (max 5)
+        1 + 32 + // pending_authority (Option<Pubkey>)
+        1 + // admin_count
+        4 + (32 * Self::MAX_MODERATORS) + // moderators (max 5)
         4 + 50 + // name
         1 + // category
         1 + // quorum_percentage
         8 + // total_members
+        1 + 8 + // max_members (Option<u64>)
+        2 + // waitlist_count
         8 + // total_votes
-        8 + // fee_collected
         8 + // created_at
         1 + // is_active
         1 + // requires_approval
+        1 + // tier
+        1 + // cancel_penalty_percentage
+        8 + // membership_fee
+        8 + // membership_period
+        2 + // member_permissions
+        8 + // request_cooldown
+        1 + 32 + // council (Option<Pubkey>)
+        1 + 8 + // archived_at (Option<i64>)
+        1 + 32 + // parent (Option<Pubkey>)
+        2 + // child_count
+        8 + // moderation_log_count
         1; // bump
+    
+    pub const DEFAULT_CANCEL_PENALTY_PERCENTAGE: u8 = 10;
+    pub const MAX_ADMINS: u8 = 10;
+    pub const MAX_MODERATORS: usize = 5;
+    pub const MAX_CHILDREN: u16 = 16; // Todas caben como remaining_accounts en un voto federado
+    pub const MIN_MEMBERSHIP_PERIOD: i64 = 86_400;      // 1 día
+    pub const MAX_MEMBERSHIP_PERIOD: i64 = 31_536_000;  // 1 año
+    pub const MAX_RENEWAL_PERIODS: u8 = 12;
+    pub const MAX_MEMBERSHIP_FEE: u64 = 100_000_000_000; // 100 SOL por periodo
+    pub const DEFAULT_MEMBER_PERMISSIONS: u16 = DEFAULT_MEMBER_PERMISSIONS;
+    pub const DEFAULT_REQUEST_COOLDOWN: i64 = 604_800;  // 7 días
+    pub const MAX_REQUEST_COOLDOWN: i64 = 7_776_000;    // 90 días
+    
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+    
+    // Límite efectivo: el propio de la comunidad sin superar el del tier
+    pub fn member_cap(&self) -> u64 {
+        let tier_cap = self.tier.max_members();
+        self.max_members.map_or(tier_cap, |cap| cap.min(tier_cap))
+    }
+    
+    pub fn is_full(&self) -> bool {
+        self.total_members >= self.member_cap()
+    }
+    
+    // Plaza libre para entrada directa (la cola de espera va primero)
+    pub fn has_open_seat(&self) -> bool {
+        !self.is_full() && self.waitlist_count == 0
+    }
+    
+    pub fn is_sub_community(&self) -> bool {
+        self.parent.is_some()
+    }
+    
+    pub fn is_listed_moderator(&self, user: &Pubkey) -> bool {
+        self.moderators.contains(user)
+    }
+    
+    // Quitar de la lista (al degradar, promover a admin o salir)
+    pub fn unlist_moderator(&mut self, user: &Pubkey) {
+        self.moderators.retain(|moderator| moderator != user);
+    }
+    
+    pub fn has_dues(&self) -> bool {
+        self.membership_period > 0
+    }
+}
+
+// Cambios de configuración para update_community (None = no modificar)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct CommunitySettings {
+    pub category: Option<u8>,
+    pub quorum_percentage: Option<u8>,
+    pub requires_approval: Option<bool>,
+    pub is_active: Option<bool>,
+    pub cancel_penalty_percentage: Option<u8>,
+    pub membership_fee: Option<u64>,
+    pub membership_period: Option<i64>, // 0 desactiva las cuotas
+    pub max_members: Option<u64>,       // 0 elimina el límite propio
+    pub member_permissions: Option<u16>, // Solo MEMBER_GRANTABLE_PERMISSIONS
+    pub request_cooldown: Option<i64>,  // 0 permite volver a solicitar al momento
+}
+
+impl CommunitySettings {
+    pub const LEN: usize =
+        1 + 1 + // category
+        1 + 1 + // quorum_percentage
+        1 + 1 + // requires_approval
+        1 + 1 + // is_active
+        1 + 1 + // cancel_penalty_percentage
+        1 + 8 + // membership_fee
+        1 + 8 + // membership_period
+        1 + 8 + // max_members
+        1 + 2 + // member_permissions
+        1 + 8;  // request_cooldown
+}
+
+// Metadatos de perfil de la comunidad (PDA companion para no inflar Community::LEN)
+#[account]
+pub struct CommunityProfile {
+    pub community: Pubkey,              // Comunidad asociada
+    pub description: String,            // Descripción (max 500 chars)
+    pub avatar_uri: Option<String>,     // Imagen de avatar
+    pub banner_uri: Option<String>,     // Imagen de banner
+    pub website_uri: Option<String>,    // Web oficial
+    pub social_links: Vec<String>,      // Redes sociales (max 5)
+    pub rules_uri: Option<String>,      // Documento de normas
+    pub rules_hash: Option<[u8; 32]>,   // Hash del documento de normas
+    pub updated_by: Pubkey,             // Último admin que lo editó
+    pub updated_at: i64,                // Timestamp última edición
+    pub bump: u8,                       // PDA bump
+}
+
+impl CommunityProfile {
+    pub const MAX_DESCRIPTION_LEN: usize = 500;
+    pub const MAX_URI_LEN: usize = 200;
+    pub const MAX_SOCIAL_LINKS: usize = 5;
+    pub const MAX_SOCIAL_LINK_LEN: usize = 100;
+    
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        4 + Self::MAX_DESCRIPTION_LEN + // description
+        1 + 4 + Self::MAX_URI_LEN + // avatar_uri (Option<String>)
+        1 + 4 + Self::MAX_URI_LEN + // banner_uri (Option<String>)
+        1 + 4 + Self::MAX_URI_LEN + // website_uri (Option<String>)
+        4 + (4 + Self::MAX_SOCIAL_LINK_LEN) * Self::MAX_SOCIAL_LINKS + // social_links
+        1 + 4 + Self::MAX_URI_LEN + // rules_uri (Option<String>)
+        1 + 32 + // rules_hash (Option<[u8; 32]>)
+        32 + // updated_by
+        8 + // updated_at
+        1; // bump
+}
+
+// Contenido completo del perfil para set_community_profile
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct CommunityProfileData {
+    pub description: String,
+    pub avatar_uri: Option<String>,
+    pub banner_uri: Option<String>,
+    pub website_uri: Option<String>,
+    pub social_links: Vec<String>,
+    pub rules_uri: Option<String>,
+    pub rules_hash: Option<[u8; 32]>,
 }
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use crate::state::community::CommunitySettings;
+
+// Acciones sensibles que requieren aprobación del consejo
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum CouncilAction {
+    WithdrawFees { amount: u64, recipient: Pubkey },
+    UpdateCommunity { settings: CommunitySettings },
+    TransferAuthority { new_authority: Pubkey },
+    ReplaceMember { old_member: Pubkey, new_member: Pubkey },
+    Archive,
+}
+
+impl CouncilAction {
+    const WITHDRAW_FEES_LEN: usize = 8 + 32;
+    const REPLACE_MEMBER_LEN: usize = 32 + 32;
+    
+    const fn max(a: usize, b: usize) -> usize {
+        if a > b { a } else { b }
+    }
+    
+    pub const LEN: usize = 1 + // variant
+        Self::max( // Variante más grande
+            CommunitySettings::LEN,
+            Self::max(Self::WITHDRAW_FEES_LEN, Self::REPLACE_MEMBER_LEN)
+        );
+}
+
+// Consejo de admins con aprobación M-de-N
+#[account]
+pub struct AdminCouncil {
+    pub community: Pubkey,          // Comunidad gobernada
+    pub members: Vec<Pubkey>,       // Miembros del consejo (max 7)
+    pub threshold: u8,              // Aprobaciones necesarias (M)
+    pub action_nonce: u64,          // Contador para las seeds de PendingAction
+    pub created_at: i64,            // Timestamp creación
+    pub bump: u8,                   // PDA bump
+}
+
+impl AdminCouncil {
+    pub const MAX_MEMBERS: usize = 7;
+    
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        4 + (32 * Self::MAX_MEMBERS) + // members
+        1 + // threshold
+        8 + // action_nonce
+        8 + // created_at
+        1; // bump
+    
+    pub fn is_member(&self, key: &Pubkey) -> bool {
+        self.members.contains(key)
+    }
+    
+    // Sacar un miembro sin tocar el umbral (quien llama comprueba que sigue siendo alcanzable)
+    pub fn remove_member(&mut self, key: &Pubkey) {
+        self.members.retain(|member| member != key);
+    }
+    
+    pub fn can_remove_member(&self) -> bool {
+        self.members.len() > self.threshold as usize
+    }
+}
+
+// Acción propuesta pendiente de aprobaciones
+#[account]
+pub struct PendingAction {
+    pub council: Pubkey,            // Consejo que debe aprobar
+    pub community: Pubkey,          // Comunidad afectada
+    pub proposer: Pubkey,           // Miembro que la propuso (recibe el rent al cerrar)
+    pub action: CouncilAction,      // Acción a ejecutar
+    pub approvals: Vec<Pubkey>,     // Miembros que han aprobado
+    pub nonce: u64,                 // Nonce usado en las seeds
+    pub created_at: i64,            // Timestamp propuesta
+    pub expires_at: i64,            // Deja de poder aprobarse/ejecutarse
+    pub bump: u8,                   // PDA bump
+}
+
+impl PendingAction {
+    pub const TTL: i64 = 259_200; // 3 días
+    
+    pub const LEN: usize = 8 + // discriminator
+        32 + // council
+        32 + // community
+        32 + // proposer
+        CouncilAction::LEN + // action
+        4 + (32 * AdminCouncil::MAX_MEMBERS) + // approvals
+        8 + // nonce
+        8 + // created_at
+        8 + // expires_at
+        1; // bump
+    
+    pub fn is_expired(&self, current_timestamp: i64) -> bool {
+        current_timestamp >= self.expires_at
+    }
+    
+    pub fn has_approved(&self, key: &Pubkey) -> bool {
+        self.approvals.contains(key)
+    }
+    
+    // Solo cuentan las aprobaciones de quien sigue en el consejo (tras rotaciones)
+    pub fn valid_approvals(&self, council: &AdminCouncil) -> usize {
+        self.approvals.iter().filter(|member| council.is_member(member)).count()
+    }
+}
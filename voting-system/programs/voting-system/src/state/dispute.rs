@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Copy)]
+pub enum DisputeStatus {
+    Open,        // Pendiente de resolución por moderadores
+    Upheld,      // Respuesta del creator confirmada - gana el creator
+    Overturned,  // Respuesta corregida o pregunta anulada - gana el challenger
+}
+
+// Disputa sobre la respuesta revelada de una pregunta Knowledge
+#[account]
+pub struct AnswerDispute {
+    pub vote: Pubkey,                    // Votación Knowledge disputada
+    pub community: Pubkey,               // Comunidad de la votación
+    pub challenger: Pubkey,              // Votante que abre la disputa
+    pub creator: Pubkey,                 // Creator de la pregunta
+    pub bond: u64,                       // Fianza en lamports (misma para ambas partes)
+    pub creator_bond_posted: bool,       // Si el creator igualó la fianza
+    pub proposed_answer: u8,             // Opción que el challenger considera correcta
+    pub reason: String,                  // Argumento (max 200 chars)
+    pub status: DisputeStatus,           // Open | Upheld | Overturned
+    pub resolved_by: Option<Pubkey>,     // Moderador que resolvió
+    pub opened_at: i64,                  // Timestamp apertura
+    pub resolved_at: Option<i64>,        // Timestamp resolución
+    pub bump: u8,
+}
+
+impl AnswerDispute {
+    pub const MIN_BOND: u64 = 50_000_000; // 0.05 SOL
+    
+    // Fianza que el creator deposita en el vote al publicar una pregunta Knowledge
+    pub const CREATOR_ANSWER_BOND: u64 = Self::MIN_BOND;
+    
+    // Plazo del creator para igualar la fianza antes de que se pueda resolver sin él
+    pub const RESPONSE_PERIOD: i64 = 86_400; // 24 horas
+    
+    // Reputación que pierde el creator si su respuesta es anulada
+    pub const CREATOR_SLASH_REPUTATION: u64 = 20;
+    
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vote
+        32 + // community
+        32 + // challenger
+        32 + // creator
+        8 + // bond
+        1 + // creator_bond_posted
+        1 + // proposed_answer
+        4 + 200 + // reason
+        1 + // status
+        1 + 32 + // resolved_by (Option<Pubkey>)
+        8 + // opened_at
+        1 + 8 + // resolved_at (Option<i64>)
+        1; // bump
+    
+    pub fn is_response_window_open(&self, current_timestamp: i64) -> bool {
+        current_timestamp <= self.opened_at + Self::RESPONSE_PERIOD
+    }
+    
+    // Se puede resolver cuando el creator respondió o dejó pasar su plazo
+    pub fn can_be_resolved(&self, current_timestamp: i64) -> bool {
+        self.status == DisputeStatus::Open &&
+        (self.creator_bond_posted || !self.is_response_window_open(current_timestamp))
+    }
+}
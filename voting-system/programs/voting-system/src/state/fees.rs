@@ -44,6 +44,25 @@ impl CommunityTier {
             CommunityTier::Premium => 500_000_000,  // 0.5 SOL
         }
     }
+    
+    // Límite de miembros según el tier
+    pub fn max_members(&self) -> u64 {
+        match self {
+            CommunityTier::Public => 1_000,
+            CommunityTier::Private => 1_000,
+            CommunityTier::Premium => 10_000,
+        }
+    }
+    
+    // Las comunidades privadas siempre requieren aprobación
+    pub fn forces_approval(&self) -> bool {
+        matches!(self, CommunityTier::Private)
+    }
+    
+    // Categorías personalizadas solo en Premium
+    pub fn allows_custom_categories(&self) -> bool {
+        matches!(self, CommunityTier::Premium)
+    }
 }
 
 #[account]
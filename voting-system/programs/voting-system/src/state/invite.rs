@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+// Lista de invitaciones (Merkle root de wallets invitadas) de una comunidad
+#[account]
+pub struct InviteList {
+    pub community: Pubkey,          // Comunidad
+    pub merkle_root: [u8; 32],      // Root del árbol de wallets invitadas
+    pub expires_at: i64,            // Las invitaciones dejan de valer en este momento
+    pub is_revoked: bool,           // Revocada por un admin
+    pub total_joined: u32,          // Miembros que entraron con invitación
+    pub created_by: Pubkey,         // Admin que publicó el root actual
+    pub updated_at: i64,            // Timestamp de la última publicación
+    pub bump: u8,                   // PDA bump
+}
+
+impl InviteList {
+    pub const MAX_DURATION_HOURS: u32 = 2_160; // 90 días
+    pub const MAX_PROOF_LEN: usize = 20;       // ~1M wallets
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // merkle_root
+        8 +  // expires_at
+        1 +  // is_revoked
+        4 +  // total_joined
+        32 + // created_by
+        8 +  // updated_at
+        1;   // bump
+
+    pub fn is_usable(&self, current_timestamp: i64) -> bool {
+        !self.is_revoked && current_timestamp < self.expires_at
+    }
+
+    // Hoja del árbol: hash de la wallet invitada
+    pub fn leaf(wallet: &Pubkey) -> [u8; 32] {
+        hashv(&[wallet.as_ref()]).to_bytes()
+    }
+
+    // Verificación con pares ordenados (no hace falta indicar izquierda/derecha)
+    pub fn verify(&self, proof: &[[u8; 32]], wallet: &Pubkey) -> bool {
+        let mut computed = Self::leaf(wallet);
+        for node in proof {
+            computed = if computed <= *node {
+                hashv(&[&computed, node]).to_bytes()
+            } else {
+                hashv(&[node, &computed]).to_bytes()
+            };
+        }
+        computed == self.merkle_root
+    }
+}
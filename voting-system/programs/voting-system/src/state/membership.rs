@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::roles::{ADMIN_PERMISSIONS, MODERATOR_PERMISSIONS};
 
 // Enum para roles de usuario
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Copy)]
@@ -36,6 +37,7 @@ pub enum ModerationAction {
     RemoveMember,    // Nueva acción para remover miembros
     ApproveMembership,  // Nueva acción para aprobar membresías
     RejectMembership,   // Nueva acción para rechazar membresías
+    UpdateCommunity,    // Cambio de configuración de la comunidad
 }
 
 // Enum para estados de solicitud de membresía
@@ -54,6 +56,9 @@ pub struct Membership {
     pub role: UserRole,             // Rol del usuario en la comunidad
     pub joined_at: i64,             // Timestamp cuando se unió
     pub is_active: bool,            // Membresía activa/inactiva
+    pub expires_at: Option<i64>,    // Fin del periodo pagado (None = sin cuotas)
+    pub custom_role: Option<Pubkey>, // CommunityRole asignado (permisos extra)
+    pub custom_role_assigned_at: i64, // Timestamp de la asignación (detecta roles recreados)
     pub bump: u8,                   // PDA bump
 }
 
@@ -64,6 +69,9 @@ impl Membership {
         1 +  // role
         8 +  // joined_at
         1 +  // is_active
+        1 + 8 + // expires_at (Option<i64>)
+        1 + 32 + // custom_role (Option<Pubkey>)
+        8 +  // custom_role_assigned_at
         1;   // bump
         
     pub fn is_moderator(&self) -> bool {
@@ -77,6 +85,27 @@ impl Membership {
     pub fn can_moderate(&self) -> bool {
         self.is_active && self.is_moderator() && self.role != UserRole::Banned
     }
+    
+    // Permisos efectivos: los del rol fijo más los del rol personalizado
+    pub fn permissions(&self, member_permissions: u16, custom_role_permissions: u16) -> u16 {
+        if !self.is_active {
+            return 0;
+        }
+        
+        let base = match self.role {
+            UserRole::Admin => ADMIN_PERMISSIONS,
+            UserRole::Moderator => MODERATOR_PERMISSIONS | member_permissions,
+            UserRole::Member => member_permissions,
+            UserRole::Banned => return 0,
+        };
+        
+        base | custom_role_permissions
+    }
+    
+    // Activa y con las cuotas al día
+    pub fn is_in_good_standing(&self, current_timestamp: i64) -> bool {
+        self.is_active && self.expires_at.is_none_or(|expires_at| current_timestamp < expires_at)
+    }
 }
 
 // Account para registros de ban
@@ -156,4 +185,9 @@ impl MembershipRequest {
         1 + 8 +  // reviewed_at (Option<i64>)
         4 + 200 + // admin_notes (String max 200 chars)
         1;   // bump
+    
+    // Fin del cooldown tras un rechazo
+    pub fn can_resubmit_at(&self, cooldown: i64) -> i64 {
+        self.reviewed_at.unwrap_or(self.requested_at) + cooldown
+    }
 }
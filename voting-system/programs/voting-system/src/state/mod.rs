@@ -8,6 +8,13 @@ pub mod moderation;
 pub mod reports; // Habilitado de nuevo
 pub mod categories;
 pub mod leaderboards; // TAREA 2.6.1-2.6.2: Módulo leaderboards
+pub mod session;
+pub mod tournament;
+pub mod dispute;
+pub mod council;
+pub mod invite;
+pub mod waitlist;
+pub mod roles;
 
 pub use user::*;
 pub use community::*;
@@ -18,4 +25,11 @@ pub use fees::*;
 pub use moderation::*;
 pub use categories::*;
 pub use leaderboards::*; // TAREA 2.6: Import leaderboards structs
+pub use session::*;
+pub use tournament::*;
+pub use dispute::*;
+pub use council::*;
+pub use invite::*;
+pub use waitlist::*;
+pub use roles::*;
 // pub use reports::*; // Solo importar específicamente para evitar conflictos
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
 #[account]
 pub struct Participation {
@@ -6,6 +7,9 @@ pub struct Participation {
     pub vote: Pubkey,               // Votación en la que participó
     pub option_selected: u8,        // Opción elegida (0-3)
     pub voted_at: i64,              // Timestamp del voto
+    pub reward_claimed: bool,       // Bonus Knowledge ya liquidado
+    pub ballot_commitment: Option<[u8; 32]>, // Voto sellado: hash(option || salt || user)
+    pub is_revealed: bool,          // option_selected es válido (siempre true si no es sellado)
     pub bump: u8,                   // PDA bump
 }
 
@@ -15,5 +19,34 @@ impl Participation {
         32 + // vote
         1 + // option_selected
         8 + // voted_at
+        1 + // reward_claimed
+        1 + 32 + // ballot_commitment (Option<[u8; 32]>)
+        1 + // is_revealed
+        1; // bump
+    
+    // Commit del voto sellado; incluye la wallet para que no se pueda copiar el de otro
+    pub fn compute_ballot_commitment(option_selected: u8, salt: &[u8; 32], user: &Pubkey) -> [u8; 32] {
+        hashv(&[&[option_selected], salt, user.as_ref()]).to_bytes()
+    }
+}
+
+// Registro de voto de confianza: uno por (vote, user)
+#[account]
+pub struct ConfidenceParticipation {
+    pub user: Pubkey,               // Usuario que votó confianza
+    pub vote: Pubkey,               // Votación Knowledge
+    pub is_confident: bool,         // A favor / en contra de la respuesta
+    pub weight: f32,                // Peso aplicado (1.0 si no es ponderada)
+    pub voted_at: i64,              // Timestamp del voto
+    pub bump: u8,                   // PDA bump
+}
+
+impl ConfidenceParticipation {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        32 + // vote
+        1 + // is_confident
+        4 + // weight (f32)
+        8 + // voted_at
         1; // bump
 }
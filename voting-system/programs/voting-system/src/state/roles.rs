@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+// Permisos (bitflags) que puede conceder un rol
+pub const PERMISSION_CREATE_VOTE: u16 = 1 << 0;        // create_voting / create_knowledge_voting
+pub const PERMISSION_REVIEW_REPORTS: u16 = 1 << 1;     // review_report
+pub const PERMISSION_BAN: u16 = 1 << 2;                // ban_user
+pub const PERMISSION_APPROVE_MEMBERS: u16 = 1 << 3;    // approve_membership / reject_membership
+pub const PERMISSION_WITHDRAW: u16 = 1 << 4;           // withdraw_fees
+pub const PERMISSION_MANAGE_CATEGORIES: u16 = 1 << 5;  // create_custom_category
+pub const PERMISSION_ALL: u16 = PERMISSION_CREATE_VOTE |
+    PERMISSION_REVIEW_REPORTS |
+    PERMISSION_BAN |
+    PERMISSION_APPROVE_MEMBERS |
+    PERMISSION_WITHDRAW |
+    PERMISSION_MANAGE_CATEGORIES;
+
+// Permisos implícitos de los roles fijos de UserRole
+pub const MODERATOR_PERMISSIONS: u16 = PERMISSION_REVIEW_REPORTS | PERMISSION_BAN;
+pub const ADMIN_PERMISSIONS: u16 = PERMISSION_ALL & !PERMISSION_WITHDRAW; // Retirar: authority o tesorero
+
+// Lo que la comunidad puede conceder a todos los miembros (Community.member_permissions)
+pub const MEMBER_GRANTABLE_PERMISSIONS: u16 = PERMISSION_CREATE_VOTE;
+pub const DEFAULT_MEMBER_PERMISSIONS: u16 = PERMISSION_CREATE_VOTE;
+
+// Rol con nombre definido por una comunidad ("tesorero", "proponente", ...)
+#[account]
+pub struct CommunityRole {
+    pub community: Pubkey,          // Comunidad propietaria
+    pub name: String,               // Nombre (max 32 chars)
+    pub permissions: u16,           // Permisos concedidos (bitflags)
+    pub created_by: Pubkey,         // Admin que lo creó
+    pub created_at: i64,            // Timestamp creación
+    pub updated_at: i64,            // Timestamp último cambio de permisos
+    pub bump: u8,                   // PDA bump
+}
+
+impl CommunityRole {
+    pub const MAX_NAME_LEN: usize = 32;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        4 + Self::MAX_NAME_LEN + // name
+        2 +  // permissions
+        32 + // created_by
+        8 +  // created_at
+        8 +  // updated_at
+        1;   // bump
+
+    pub fn is_valid_permissions(permissions: u16) -> bool {
+        permissions != 0 && permissions & !PERMISSION_ALL == 0
+    }
+}
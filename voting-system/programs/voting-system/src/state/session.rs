@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+// Scopes (bitflags) que puede ejecutar una session key
+pub const SESSION_SCOPE_CAST_VOTE: u8 = 1 << 0;        // cast_vote
+pub const SESSION_SCOPE_VOTE_CONFIDENCE: u8 = 1 << 1;  // vote_confidence
+pub const SESSION_SCOPE_ALL_VOTING: u8 = SESSION_SCOPE_CAST_VOTE | SESSION_SCOPE_VOTE_CONFIDENCE;
+
+// Account para session keys efímeras (votar sin popup de wallet)
+#[account]
+pub struct SessionKey {
+    pub authority: Pubkey,          // Wallet que autoriza la sesión
+    pub session_key: Pubkey,        // Clave efímera del navegador
+    pub community: Pubkey,          // Comunidad a la que se limita la sesión
+    pub scopes: u8,                 // Instrucciones permitidas (bitflags)
+    pub expires_at: i64,            // Timestamp de expiración
+    pub max_uses: u32,              // Número máximo de usos
+    pub uses: u32,                  // Usos consumidos
+    pub created_at: i64,            // Timestamp creación
+    pub bump: u8,                   // PDA bump
+}
+
+impl SessionKey {
+    pub const MAX_DURATION_HOURS: u32 = 168; // 1 semana
+    pub const MAX_USES: u32 = 1_000;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        32 + // session_key
+        32 + // community
+        1 +  // scopes
+        8 +  // expires_at
+        4 +  // max_uses
+        4 +  // uses
+        8 +  // created_at
+        1;   // bump
+
+    pub fn is_valid_scope(scopes: u8) -> bool {
+        scopes != 0 && scopes & !SESSION_SCOPE_ALL_VOTING == 0
+    }
+
+    pub fn allows(&self, scope: u8) -> bool {
+        self.scopes & scope == scope
+    }
+
+    pub fn is_expired(&self, current_timestamp: i64) -> bool {
+        current_timestamp >= self.expires_at
+    }
+
+    pub fn has_uses_left(&self) -> bool {
+        self.uses < self.max_uses
+    }
+}
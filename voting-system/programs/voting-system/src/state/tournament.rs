@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Copy)]
+pub enum TournamentStatus {
+    Active,     // Aceptando rondas, participantes y puntuaciones
+    Finalized,  // Ranking cerrado y premios pagados
+}
+
+// Entrada del ranking del torneo
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TournamentStanding {
+    pub user: Pubkey,                        // Participante
+    pub correct_answers: u32,                // Respuestas correctas acumuladas
+    pub last_scored_at: i64,                 // Momento del último acierto (desempate)
+}
+
+// Torneo de preguntas Knowledge en varias rondas
+#[account]
+pub struct Tournament {
+    pub community: Pubkey,                   // Comunidad organizadora
+    pub creator: Pubkey,                     // Quien crea las rondas y financia el premio
+    pub name: String,                        // Nombre (max 50 chars)
+    pub total_rounds: u8,                    // Rondas previstas
+    pub rounds: Vec<Pubkey>,                 // Votaciones Knowledge de cada ronda
+    pub prize_pool: u64,                     // Premio en lamports depositado en el account
+    pub total_participants: u32,             // Participantes inscritos
+    pub standings: Vec<TournamentStanding>,  // Top 3 del torneo
+    pub scoring_deadline: i64,               // Límite para registrar puntuaciones
+    pub status: TournamentStatus,            // Active | Finalized
+    pub created_at: i64,                     // Timestamp creación
+    pub finalized_at: Option<i64>,           // Timestamp finalización
+    pub bump: u8,
+}
+
+// Puntuación acumulada de un participante
+#[account]
+pub struct TournamentScore {
+    pub tournament: Pubkey,                  // Torneo
+    pub user: Pubkey,                        // Participante (wallet)
+    pub correct_answers: u32,                // Respuestas correctas
+    pub rounds_scored: u32,                  // Rondas registradas
+    pub scored_rounds_mask: u32,             // Bitmap de rondas ya registradas
+    pub last_scored_at: i64,                 // Momento del último acierto
+    pub joined_at: i64,                      // Timestamp inscripción
+    pub bump: u8,
+}
+
+impl TournamentStanding {
+    pub const LEN: usize =
+        32 + // user
+        4 +  // correct_answers
+        8;   // last_scored_at
+}
+
+impl Tournament {
+    pub const MAX_ROUNDS: usize = 20;
+    pub const MAX_STANDINGS: usize = 3;      // Top 3 premiados
+    pub const PRIZE_SHARES: [u64; 3] = [50, 30, 20]; // % del premio por posición
+
+    // Tiempo para registrar puntuaciones tras cerrar la última ronda
+    pub const SCORING_PERIOD: i64 = 172_800; // 48 horas
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // creator
+        4 + 50 + // name
+        1 + // total_rounds
+        4 + (32 * Self::MAX_ROUNDS) + // rounds
+        8 + // prize_pool
+        4 + // total_participants
+        4 + (Self::MAX_STANDINGS * TournamentStanding::LEN) + // standings
+        8 + // scoring_deadline
+        1 + // status
+        8 + // created_at
+        1 + 8 + // finalized_at (Option<i64>)
+        1; // bump
+
+    pub fn round_index(&self, vote: &Pubkey) -> Option<usize> {
+        self.rounds.iter().position(|round| round == vote)
+    }
+
+    // Añadir o actualizar participante en el ranking (las puntuaciones solo crecen)
+    pub fn update_standings(&mut self, standing: TournamentStanding) {
+        if let Some(pos) = self.standings.iter().position(|s| s.user == standing.user) {
+            self.standings[pos] = standing;
+        } else {
+            self.standings.push(standing);
+        }
+
+        // Más aciertos primero; a igualdad, quien acertó antes
+        self.standings.sort_by(|a, b| {
+            b.correct_answers
+                .cmp(&a.correct_answers)
+                .then(a.last_scored_at.cmp(&b.last_scored_at))
+        });
+        self.standings.truncate(Self::MAX_STANDINGS);
+    }
+
+    // Premio para una posición del ranking (0-indexed)
+    pub fn prize_for_rank(&self, rank: usize) -> u64 {
+        (self.prize_pool * Self::PRIZE_SHARES[rank]) / 100
+    }
+}
+
+impl TournamentScore {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // tournament
+        32 + // user
+        4 + // correct_answers
+        4 + // rounds_scored
+        4 + // scored_rounds_mask
+        8 + // last_scored_at
+        8 + // joined_at
+        1; // bump
+
+    pub fn is_round_scored(&self, round: usize) -> bool {
+        self.scored_rounds_mask & (1 << round) != 0
+    }
+}
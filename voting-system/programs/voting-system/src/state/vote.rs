@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Copy)]
 pub enum VoteType {
@@ -14,6 +15,33 @@ pub enum VoteStatus {
     Failed,         // Votación fallida por quorum
     AwaitingReveal, // Esperando revelación de respuesta
     ConfidenceVoting, // Votación de confianza activa
+    Disputed,       // Respuesta en disputa, recompensas bloqueadas
+}
+
+// Bonus por rapidez en Knowledge: decae linealmente de max_bonus (al crear) a min_bonus (en el deadline)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Copy)]
+pub struct SpeedBonus {
+    pub max_bonus: u8,  // Puntos extra al responder nada más abrir la votación
+    pub min_bonus: u8,  // Puntos extra al responder justo en el deadline
+}
+
+impl SpeedBonus {
+    pub const MAX_POINTS: u8 = 10;
+    
+    pub fn is_valid(&self) -> bool {
+        self.min_bonus <= self.max_bonus && self.max_bonus <= Self::MAX_POINTS
+    }
+}
+
+// Ajustes opcionales de create_voting (quorum, ponderación, bonus, sellado)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct VoteSettings {
+    pub quorum_required: u64,
+    pub quorum_percentage: Option<u8>,
+    pub use_percentage_quorum: bool,
+    pub weighted_voting_enabled: bool,     // Pondera los votos de confianza por voting_weight
+    pub speed_bonus: Option<SpeedBonus>,   // Solo Knowledge
+    pub sealed_results: bool,              // Siempre true en Knowledge
 }
 
 #[account]
@@ -37,12 +65,22 @@ pub struct Vote {
     // Campos para sistema commit-reveal (2.4.3-2.4.6)
     pub answer_hash: Option<[u8; 32]>,     // Hash respuesta oculta
     pub revealed_answer: Option<String>,   // Respuesta revelada
+    pub explanation_hash: Option<[u8; 32]>, // Hash del texto de explicación (off-chain)
+    pub explanation_uri: Option<String>,   // URI de la explicación/fuentes (ar://, ipfs://, https://)
     pub reveal_deadline: Option<i64>,      // Deadline para revelar
     pub confidence_votes_for: u32,         // Votos confianza a favor
     pub confidence_votes_against: u32,     // Votos confianza contra
+    pub confidence_weight_for: f32,        // Peso acumulado a favor (votación ponderada)
+    pub confidence_weight_against: f32,    // Peso acumulado en contra (votación ponderada)
     pub confidence_deadline: Option<i64>,  // Deadline votación confianza
     pub weighted_voting_enabled: bool,     // TAREA 2.5.7: Votación ponderada opcional
     pub weighted_results: Vec<f32>,         // Resultados ponderados por reputación
+    pub speed_bonus: Option<SpeedBonus>,   // Bonus por rapidez (solo Knowledge)
+    pub sealed_results: bool,              // Votos sellados (commit-reveal), siempre en Knowledge
+    pub revealed_ballots: u64,             // Votos sellados ya revelados
+    pub answer_validated: bool,            // Knowledge: respuesta aceptada por la confianza o la disputa
+    pub answer_bond: u64,                  // Knowledge: fianza del creator (vuelve al cerrar el account)
+    pub federated_members: Option<u64>,    // Miembros de padre + hijas (voto federado)
     pub bump: u8,
 }
 
@@ -67,16 +105,60 @@ impl Vote {
         // Campos commit-reveal
         1 + 32 + // answer_hash (Option<[u8; 32]>)
         1 + 4 + 200 + // revealed_answer (Option<String>)
+        1 + 32 + // explanation_hash (Option<[u8; 32]>)
+        1 + 4 + Self::MAX_EXPLANATION_URI_LEN + // explanation_uri (Option<String>)
         1 + 8 + // reveal_deadline (Option<i64>)
         4 + // confidence_votes_for
         4 + // confidence_votes_against
+        4 + // confidence_weight_for (f32)
+        4 + // confidence_weight_against (f32)
         1 + 8 + // confidence_deadline (Option<i64>)
         1 + // weighted_voting_enabled
         4 + (4 * 4) + // weighted_results (Vec<f32>, max 4)
+        1 + 2 + // speed_bonus (Option<SpeedBonus>)
+        1 + // sealed_results
+        8 + // revealed_ballots
+        1 + // answer_validated
+        8 + // answer_bond
+        1 + 8 + // federated_members (Option<u64>)
         1; // bump
     
+    pub const MAX_EXPLANATION_URI_LEN: usize = 200;
+    
+    // Ventana en la que el creator puede cancelar aunque ya haya votos
+    pub const CANCEL_GRACE_PERIOD: i64 = 600; // 10 minutos
+    
+    // Plazo del creator para revelar la respuesta tras el deadline
+    pub const REVEAL_PERIOD: i64 = 172_800; // 48 horas
+    
+    // TAREA 2.5.4: Puntos de precisión por respuesta correcta
+    pub const KNOWLEDGE_BONUS: u64 = 3;
+    
+    // Tiempo que el account se conserva tras finalizar (para reclamaciones)
+    pub const CLOSE_DELAY: i64 = 604_800; // 7 días
+    
+    // Bonus por rapidez según cuándo se emitió el voto dentro de [created_at, deadline]
+    pub fn speed_bonus_for(&self, voted_at: i64) -> u64 {
+        let Some(bonus) = self.speed_bonus else {
+            return 0;
+        };
+        
+        let duration = (self.deadline - self.created_at).max(1);
+        let elapsed = (voted_at - self.created_at).clamp(0, duration);
+        let range = (bonus.max_bonus - bonus.min_bonus) as i64;
+        
+        (bonus.max_bonus as i64 - (range * elapsed) / duration) as u64
+    }
+    
+    // Commit de la respuesta correcta: hash(correct_answer || salt)
+    pub fn compute_answer_hash(correct_answer: u8, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&[correct_answer], salt]).to_bytes()
+    }
+    
     // Método para calcular quorum dinámico
     pub fn calculate_required_quorum(&self, total_members: u64) -> u64 {
+        // En votos federados el quorum se mide sobre toda la federación
+        let total_members = self.federated_members.unwrap_or(total_members);
         if self.use_percentage_quorum {
             if let Some(percentage) = self.quorum_percentage {
                 // Calcular quorum por porcentaje de miembros totales
@@ -91,6 +173,10 @@ impl Vote {
         }
     }
     
+    pub fn is_federated(&self) -> bool {
+        self.federated_members.is_some()
+    }
+    
     // Verificar si se ha alcanzado el quorum
     pub fn has_reached_quorum(&self, total_members: u64) -> bool {
         self.total_votes >= self.calculate_required_quorum(total_members)
@@ -101,6 +187,50 @@ impl Vote {
         current_timestamp >= self.deadline
     }
     
+    // Los votos sellados se revelan entre el deadline y reveal_deadline
+    pub fn is_ballot_reveal_open(&self, current_timestamp: i64) -> bool {
+        self.sealed_results &&
+        self.is_expired(current_timestamp) &&
+        self.reveal_deadline.is_some_and(|deadline| current_timestamp <= deadline)
+    }
+    
+    // Verificar si el creator todavía puede cancelar la votación
+    pub fn can_be_cancelled(&self, current_timestamp: i64) -> bool {
+        self.total_votes == 0 || current_timestamp - self.created_at <= Self::CANCEL_GRACE_PERIOD
+    }
+    
+    // Cancelada con votos: el account sigue abierto hasta que cada votante recupere su participation
+    pub fn has_pending_ballot_refunds(&self) -> bool {
+        self.status == VoteStatus::Cancelled && self.total_votes > 0
+    }
+    
+    // Verificar si el account puede cerrarse para recuperar el rent
+    pub fn can_be_closed(&self, current_timestamp: i64) -> bool {
+        if self.status == VoteStatus::Cancelled {
+            return self.total_votes == 0;
+        }
+        
+        let finished_at = self.confidence_deadline.unwrap_or(self.deadline).max(self.deadline);
+        matches!(self.status, VoteStatus::Completed | VoteStatus::Failed) &&
+        current_timestamp >= finished_at + Self::CLOSE_DELAY
+    }
+    
+    // TAREA 2.4.6: La respuesta se valida con 60% de confianza (por peso si es ponderada)
+    pub fn is_answer_validated(&self) -> bool {
+        if self.weighted_voting_enabled {
+            let total_weight = self.confidence_weight_for + self.confidence_weight_against;
+            self.confidence_weight_for >= total_weight * 0.6
+        } else {
+            let total_confidence_votes = self.confidence_votes_for + self.confidence_votes_against;
+            self.confidence_votes_for >= (total_confidence_votes * 60) / 100
+        }
+    }
+    
+    // Knowledge finalizada con la respuesta aceptada: solo entonces hay bonus y puntuación
+    pub fn has_validated_answer(&self) -> bool {
+        self.status == VoteStatus::Completed && self.answer_validated && self.correct_answer.is_some()
+    }
+    
     // Verificar si la votación falló por quorum
     pub fn should_fail_for_quorum(&self, total_members: u64, current_timestamp: i64) -> bool {
         self.is_expired(current_timestamp) && !self.has_reached_quorum(total_members)
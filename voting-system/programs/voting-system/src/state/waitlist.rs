@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+// Entrada de la lista de espera
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct WaitlistEntry {
+    pub user: Pubkey,               // Wallet en espera
+    pub deposit: u64,               // Rent de la futura membership (reembolsa al crank)
+    pub joined_at: i64,             // Timestamp de entrada en la cola
+}
+
+// Cola FIFO para comunidades llenas
+#[account]
+pub struct Waitlist {
+    pub community: Pubkey,          // Comunidad
+    pub entries: Vec<WaitlistEntry>, // Cola (max 50), el primero entra antes
+    pub total_admitted: u32,        // Usuarios admitidos desde la cola
+    pub bump: u8,                   // PDA bump
+}
+
+impl WaitlistEntry {
+    pub const LEN: usize =
+        32 + // user
+        8 +  // deposit
+        8;   // joined_at
+}
+
+impl Waitlist {
+    pub const MAX_ENTRIES: usize = 50;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        4 + (Self::MAX_ENTRIES * WaitlistEntry::LEN) + // entries
+        4 +  // total_admitted
+        1;   // bump
+
+    pub fn position(&self, user: &Pubkey) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.user == *user)
+    }
+
+    pub fn next_user(&self) -> Option<Pubkey> {
+        self.entries.first().map(|entry| entry.user)
+    }
+}
@@ -1,13 +1,209 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { VotingSystem } from "../target/types/voting_system";
+import IDL from "../target/idl/voting_system.json";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { 
   Keypair, 
   LAMPORTS_PER_SOL, 
   PublicKey, 
   SystemProgram,
+  Transaction,
 } from "@solana/web3.js";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+
+// ============================================================================
+// 🧰 HELPERS: cada bloque crea sus propios usuarios, comunidades y votaciones
+// ============================================================================
+
+type Fund = (wallet: PublicKey, lamports: number) => Promise<void>;
+
+const testKit = (program: Program<VotingSystem>, fund: Fund) => {
+  const pda = (...seeds: Buffer[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  const feePool = pda(Buffer.from("fee_pool"));
+
+  const userPdaOf = (wallet: PublicKey) => pda(Buffer.from("user"), wallet.toBuffer());
+
+  const membershipPdaOf = (community: PublicKey, wallet: PublicKey) =>
+    pda(Buffer.from("membership"), community.toBuffer(), wallet.toBuffer());
+
+  const vaultPdaOf = (community: PublicKey) =>
+    pda(Buffer.from("community_vault"), community.toBuffer());
+
+  const votePdaOf = (community: PublicKey, creator: PublicKey) =>
+    pda(Buffer.from("vote"), community.toBuffer(), creator.toBuffer());
+
+  const participationPdaOf = (vote: PublicKey, wallet: PublicKey) =>
+    pda(Buffer.from("participation"), vote.toBuffer(), wallet.toBuffer());
+
+  const councilPdaOf = (community: PublicKey) =>
+    pda(Buffer.from("council"), community.toBuffer());
+
+  const pendingActionPdaOf = (council: PublicKey, nonce: anchor.BN) =>
+    pda(Buffer.from("pending_action"), council.toBuffer(), nonce.toArrayLike(Buffer, "le", 8));
+
+  // Cada acción de moderación crea un log nuevo: nonce = moderation_log_count de la comunidad
+  const moderationLogPdaOf = async (community: PublicKey) => {
+    const { moderationLogCount } = await program.account.community.fetch(community);
+    return pda(Buffer.from("moderation_log"), community.toBuffer(), moderationLogCount.toArrayLike(Buffer, "le", 8));
+  };
+
+  // Ajustes de create_voting (VoteSettings); quorum alto para que no se complete sola
+  const voteSettings = (overrides: Record<string, unknown> = {}) => ({
+    quorumRequired: new anchor.BN(10),
+    quorumPercentage: null,
+    usePercentageQuorum: false,
+    weightedVotingEnabled: false,
+    speedBonus: null,
+    sealedResults: false,
+    ...overrides,
+  });
+
+  // Cambios de update_community (CommunitySettings): null = no modificar
+  const communitySettings = (overrides: Record<string, unknown> = {}) => ({
+    category: null,
+    quorumPercentage: null,
+    requiresApproval: null,
+    isActive: null,
+    cancelPenaltyPercentage: null,
+    membershipFee: null,
+    membershipPeriod: null,
+    maxMembers: null,
+    memberPermissions: null,
+    requestCooldown: null,
+    ...overrides,
+  });
+
+  // Wallet con SOL y cuenta User creada
+  const newUser = async (sol = 3): Promise<Keypair> => {
+    const wallet = Keypair.generate();
+    await fund(wallet.publicKey, sol * LAMPORTS_PER_SOL);
+
+    await program.methods
+      .createUser()
+      .accounts({
+        user: userPdaOf(wallet.publicKey),
+        wallet: wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([wallet])
+      .rpc();
+
+    return wallet;
+  };
+
+  // Comunidad pública; el creator queda como Admin
+  const newCommunity = async (authority: Keypair, name: string, requiresApproval = false) => {
+    const community = pda(Buffer.from("community"), authority.publicKey.toBuffer(), Buffer.from(name));
+
+    await program.methods
+      .createCommunity(name, 1, 50, requiresApproval, { public: {} })
+      .accounts({
+        community,
+        feePool,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    return community;
+  };
+
+  const joinAs = async (member: Keypair, community: PublicKey) => {
+    const membership = membershipPdaOf(community, member.publicKey);
+
+    await program.methods
+      .joinCommunity()
+      .accounts({
+        membership,
+        community,
+        user: userPdaOf(member.publicKey),
+        member: member.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member])
+      .rpc();
+
+    return membership;
+  };
+
+  const newOpinionVote = async (creator: Keypair, community: PublicKey, settings = voteSettings()) => {
+    const vote = votePdaOf(community, creator.publicKey);
+
+    await program.methods
+      .createVoting("Should we try this?", ["Yes", "No"], { opinion: {} }, null, 24, settings)
+      .accounts({
+        vote,
+        community,
+        membership: membershipPdaOf(community, creator.publicKey),
+        user: userPdaOf(creator.publicKey),
+        creator: creator.publicKey,
+        feePool,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+    return vote;
+  };
+
+  const castVoteAs = async (voter: Keypair, vote: PublicKey, community: PublicKey, option = 0) => {
+    const participation = participationPdaOf(vote, voter.publicKey);
+
+    await program.methods
+      .castVote(option)
+      .accounts({
+        participation,
+        vote,
+        membership: membershipPdaOf(community, voter.publicKey),
+        user: userPdaOf(voter.publicKey),
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+
+    return participation;
+  };
+
+  // La instrucción debe fallar con el código de error indicado
+  const expectError = async (action: Promise<unknown>, code: string) => {
+    let failed = false;
+    try {
+      await action;
+    } catch (error: any) {
+      failed = true;
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+    expect(failed, `Should have failed with ${code}`).to.be.true;
+  };
+
+  return {
+    pda,
+    feePool,
+    userPdaOf,
+    membershipPdaOf,
+    vaultPdaOf,
+    votePdaOf,
+    participationPdaOf,
+    councilPdaOf,
+    pendingActionPdaOf,
+    moderationLogPdaOf,
+    voteSettings,
+    communitySettings,
+    newUser,
+    newCommunity,
+    joinAs,
+    newOpinionVote,
+    castVoteAs,
+    expectError,
+  };
+};
 
 describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
   const provider = anchor.AnchorProvider.env();
@@ -31,6 +227,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
   let membershipPda1: PublicKey;
   let membershipPda2: PublicKey;
   let membershipPdaAdmin: PublicKey;
+  let communityVaultPda: PublicKey;
   let participationPda1: PublicKey;
   let feePoolPda: PublicKey;
   let rewardRecordPda1: PublicKey;
@@ -89,6 +286,28 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
     );
   });
 
+  // Helpers sobre el validador local
+  const {
+    userPdaOf,
+    membershipPdaOf,
+    vaultPdaOf,
+    votePdaOf,
+    councilPdaOf,
+    pendingActionPdaOf,
+    moderationLogPdaOf,
+    newUser,
+    newCommunity,
+    joinAs,
+    newOpinionVote,
+    castVoteAs,
+    voteSettings,
+    communitySettings,
+    expectError,
+  } = testKit(program, async (wallet, lamports) => {
+    const signature = await provider.connection.requestAirdrop(wallet, lamports);
+    await provider.connection.confirmTransaction(signature);
+  });
+
   describe("👤 USER TESTS", () => {
     it("✅ Should create user successfully", async () => {
       [userPda1] = await PublicKey.findProgramAddress(
@@ -146,9 +365,10 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
       );
 
       await program.methods
-        .createCommunity(communityName, 0, 50)
+        .createCommunity(communityName, 0, 50, false, { public: {} })
         .accounts({
           community: communityPda,
+          feePool: feePoolPda,
           authority: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -241,7 +461,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
           voteType,
           correctAnswer,
           deadlineHours,
-          quorumRequired
+          voteSettings({ quorumRequired })
         )
         .accounts({
           vote: votePda,
@@ -328,12 +548,17 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
   });
 
   describe("🎮 GAMIFICATION TESTS", () => {
-    it("✅ Should test knowledge voting with bonus points", async () => {
+    it("✅ Should keep knowledge answer hidden until reveal", async () => {
       // Crear nueva voting de Knowledge
       const question = "What is 2 + 2?";
       const options = ["3", "4", "5"];
       const voteType = { knowledge: {} };
       const correctAnswer = 1; // "4"
+      const salt = Keypair.generate().publicKey.toBuffer();
+      // Commit: sha256(correct_answer || salt)
+      const answerHash = Array.from(
+        createHash("sha256").update(Buffer.concat([Buffer.from([correctAnswer]), salt])).digest()
+      );
 
       // FIX: Usar user2 como creator para evitar colisión PDA con primer voto
       // Seeds deben ser únicos: ["vote", community, creator]
@@ -352,9 +577,9 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
           question,
           options,
           voteType,
-          correctAnswer,
+          answerHash,
           12,
-          new anchor.BN(1)
+          voteSettings({ quorumRequired: new anchor.BN(1) })
         )
         .accounts({
           vote: knowledgeVotePda,
@@ -377,8 +602,16 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
 
       const initialReputation = (await program.account.user.fetch(userPda1)).reputationPoints.toNumber();
 
+      // Knowledge siempre es sellado: se envía hash(option || salt || wallet)
+      const ballotSalt = Buffer.alloc(32, 7);
+      const ballotCommitment = Array.from(
+        createHash("sha256")
+          .update(Buffer.concat([Buffer.from([1]), ballotSalt, user1.publicKey.toBuffer()]))
+          .digest()
+      );
+
       await program.methods
-        .castVote(1) // Respuesta correcta
+        .commitVote(ballotCommitment) // Respuesta correcta (oculta)
         .accounts({
           participation: knowledgeParticipationPda,
           vote: knowledgeVotePda,
@@ -393,9 +626,17 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
       const finalUserAccount = await program.account.user.fetch(userPda1);
       const finalReputation = finalUserAccount.reputationPoints.toNumber();
       
-      // Debería tener +1 por votar + 3 por respuesta correcta = +4
-      expect(finalReputation).to.equal(initialReputation + 4);
-      console.log(`✅ Knowledge voting bonus: ${initialReputation} → ${finalReputation} (+4 points)`);
+      // Solo +1 por votar: el bonus se decide tras revelar la respuesta
+      expect(finalReputation).to.equal(initialReputation + 1);
+      
+      // La votación sigue activa hasta el deadline y la respuesta no es pública
+      const knowledgeVote = await program.account.vote.fetch(knowledgeVotePda);
+      expect(knowledgeVote.status).to.deep.equal({ active: {} });
+      expect(knowledgeVote.correctAnswer).to.be.null;
+      expect(knowledgeVote.answerHash).to.deep.equal(answerHash);
+      expect(knowledgeVote.totalVotes.toNumber()).to.equal(1);
+      expect(knowledgeVote.results.map((r) => r.toNumber())).to.deep.equal(new Array(options.length).fill(0));
+      console.log(`✅ Knowledge voting: ${initialReputation} → ${finalReputation} (+1 point, answer and results hidden)`);
     });
   });
 
@@ -477,9 +718,10 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
       );
 
       await program.methods
-        .createCommunity("Test Community", 1, 50)
+        .createCommunity("Test Community", 1, 50, false, { public: {} })
         .accounts({
           community: communityPda,
+          feePool: feePoolPda,
           authority: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -490,23 +732,15 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
     });
 
     it("✅ Should join community and accumulate fees through voting", async () => {
-      // Admin joins community
+      // La membership Admin del creator se crea junto con la comunidad
       [membershipPdaAdmin] = await PublicKey.findProgramAddress(
         [Buffer.from("membership"), communityPda.toBuffer(), admin.publicKey.toBuffer()],
         program.programId
       );
 
-      await program.methods
-        .joinCommunity()
-        .accounts({
-          membership: membershipPdaAdmin,
-          community: communityPda,
-          user: userPdaAdmin,
-          member: admin.publicKey,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([admin])
-        .rpc();
+      const adminMembership = await program.account.membership.fetch(membershipPdaAdmin);
+      expect(adminMembership.role).to.deep.equal({ admin: {} });
+      expect(adminMembership.isActive).to.be.true;
 
       // Users join community
       [membershipPda1] = await PublicKey.findProgramAddress(
@@ -549,7 +783,13 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
         program.programId
       );
 
-      const initialCommunityBalance = await provider.connection.getBalance(communityPda);
+      // Los fees van al vault de la comunidad, no al account Community
+      [communityVaultPda] = await PublicKey.findProgramAddress(
+        [Buffer.from("community_vault"), communityPda.toBuffer()],
+        program.programId
+      );
+
+      const initialVaultBalance = await provider.connection.getBalance(communityVaultPda);
       
       await program.methods
         .createVoting(
@@ -558,7 +798,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
           { opinion: {} },
           null,
           24,
-          2
+          voteSettings({ quorumRequired: new anchor.BN(2) })
         )
         .accounts({
           vote: votePda,
@@ -571,8 +811,8 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
         .signers([user1])
         .rpc();
 
-      const finalCommunityBalance = await provider.connection.getBalance(communityPda);
-      const feeCollected = finalCommunityBalance - initialCommunityBalance;
+      const finalVaultBalance = await provider.connection.getBalance(communityVaultPda);
+      const feeCollected = finalVaultBalance - initialVaultBalance;
       
       expect(feeCollected).to.be.greaterThan(0);
       console.log(`✅ Fees collected: ${feeCollected} lamports (${feeCollected / LAMPORTS_PER_SOL} SOL)`);
@@ -580,9 +820,15 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
   });
 
   describe("💰 WITHDRAW FEES TESTS", () => {
+    // Fees retirables: saldo del vault por encima del mínimo rent-exempt
+    const vaultAvailable = async () => {
+      const balance = await provider.connection.getBalance(communityVaultPda);
+      const rentMinimum = await provider.connection.getMinimumBalanceForRentExemption(0);
+      return balance - rentMinimum;
+    };
+
     it("✅ Should allow admin to withdraw fees", async () => {
-      const communityAccount = await program.account.community.fetch(communityPda);
-      const feesAvailable = communityAccount.feeCollected.toNumber();
+      const feesAvailable = await vaultAvailable();
       
       expect(feesAvailable).to.be.greaterThan(0);
       
@@ -593,6 +839,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
         .withdrawFees(new anchor.BN(withdrawAmount))
         .accounts({
           community: communityPda,
+          vault: communityVaultPda,
           adminMembership: membershipPdaAdmin,
           admin: admin.publicKey,
           systemProgram: SystemProgram.programId,
@@ -601,9 +848,8 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
         .rpc();
 
       const finalAdminBalance = await provider.connection.getBalance(admin.publicKey);
-      const finalCommunityAccount = await program.account.community.fetch(communityPda);
       
-      expect(finalCommunityAccount.feeCollected.toNumber()).to.equal(feesAvailable - withdrawAmount);
+      expect(await vaultAvailable()).to.equal(feesAvailable - withdrawAmount);
       console.log(`✅ Admin withdrew ${withdrawAmount} lamports successfully`);
     });
 
@@ -613,6 +859,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
           .withdrawFees(new anchor.BN(1000000))
           .accounts({
             community: communityPda,
+            vault: communityVaultPda,
             adminMembership: membershipPda1, // user1 is not admin
             admin: user1.publicKey,
             systemProgram: SystemProgram.programId,
@@ -628,8 +875,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
     });
 
     it("❌ Should fail if trying to withdraw more than available", async () => {
-      const communityAccount = await program.account.community.fetch(communityPda);
-      const feesAvailable = communityAccount.feeCollected.toNumber();
+      const feesAvailable = await vaultAvailable();
       const excessiveAmount = feesAvailable + 1000000; // More than available
       
       try {
@@ -637,6 +883,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
           .withdrawFees(new anchor.BN(excessiveAmount))
           .accounts({
             community: communityPda,
+            vault: communityVaultPda,
             adminMembership: membershipPdaAdmin,
             admin: admin.publicKey,
             systemProgram: SystemProgram.programId,
@@ -826,14 +1073,14 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
     });
 
     it("❌ Should handle withdraw of exact remaining amount", async () => {
-      const communityAccount = await program.account.community.fetch(communityPda);
-      const exactAmount = communityAccount.feeCollected.toNumber();
+      const exactAmount = await vaultAvailable();
       
       if (exactAmount > 0) {
         await program.methods
           .withdrawFees(new anchor.BN(exactAmount))
           .accounts({
             community: communityPda,
+            vault: communityVaultPda,
             adminMembership: membershipPdaAdmin,
             admin: admin.publicKey,
             systemProgram: SystemProgram.programId,
@@ -841,8 +1088,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
           .signers([admin])
           .rpc();
 
-        const finalCommunityAccount = await program.account.community.fetch(communityPda);
-        expect(finalCommunityAccount.feeCollected.toNumber()).to.equal(0);
+        expect(await vaultAvailable()).to.equal(0);
         console.log("✅ Exact amount withdrawal successful");
       } else {
         console.log("✅ No fees to withdraw (expected)");
@@ -871,16 +1117,13 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
   describe("👥 FASE 1.5 - SISTEMA DE ROLES TESTS", () => {
     
     it("✅ Should assign moderator role successfully", async () => {
-      // Crear moderation log PDA
-      const [moderationLogPda] = await PublicKey.findProgramAddress(
-        [Buffer.from("moderation_log"), communityPda.toBuffer(), admin.publicKey.toBuffer()],
-        program.programId
-      );
+      const moderationLogPda = await moderationLogPdaOf(communityPda);
 
       await program.methods
         .assignModerator()
         .accounts({
           membership: membershipPda2, // user2 será el nuevo moderador
+          community: communityPda,
           adminMembership: membershipPdaAdmin,
           moderationLog: moderationLogPda,
           admin: admin.publicKey,
@@ -891,20 +1134,21 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
 
       const membership = await program.account.membership.fetch(membershipPda2);
       expect(membership.role).to.deep.equal({ moderator: {} });
+
+      const community = await program.account.community.fetch(communityPda);
+      expect(community.moderators.map((m: PublicKey) => m.toBase58())).to.include(user2.publicKey.toBase58());
       console.log("✅ User2 successfully assigned as moderator");
     });
 
     it("❌ Should fail to assign moderator if not admin", async () => {
-      const [moderationLogPda] = await PublicKey.findProgramAddress(
-        [Buffer.from("moderation_log"), communityPda.toBuffer(), user1.publicKey.toBuffer()],
-        program.programId
-      );
+      const moderationLogPda = await moderationLogPdaOf(communityPda);
 
       try {
         await program.methods
           .assignModerator()
           .accounts({
             membership: membershipPda1,
+            community: communityPda,
             adminMembership: membershipPda1, // user1 no es admin
             moderationLog: moderationLogPda,
             admin: user1.publicKey,
@@ -961,10 +1205,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
         .rpc();
 
       // Ahora remover el miembro
-      const [removeModerationLogPda] = await PublicKey.findProgramAddress(
-        [Buffer.from("moderation_log"), communityPda.toBuffer(), admin.publicKey.toBuffer()],
-        program.programId
-      );
+      const removeModerationLogPda = await moderationLogPdaOf(communityPda);
 
       await program.methods
         .removeMember("Inappropriate behavior")
@@ -1040,33 +1281,19 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
           "ApprovalCommunity",
           1, // Gaming category
           60, // 60% quorum
-          true // requires_approval = true
+          true, // requires_approval = true
+          { public: {} } // tier
         )
         .accounts({
           community: approvalCommunityPda,
+          feePool: feePoolPda,
           authority: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([admin])
         .rpc();
       
-      // Admin se une a la comunidad (bypasa aprobación)
-      const [adminApprovalMembershipPda] = await PublicKey.findProgramAddress(
-        [Buffer.from("membership"), approvalCommunityPda.toBuffer(), admin.publicKey.toBuffer()],
-        program.programId
-      );
-      
-      await program.methods
-        .joinCommunity()
-        .accounts({
-          membership: adminApprovalMembershipPda,
-          community: approvalCommunityPda,
-          user: userPdaAdmin,
-          member: admin.publicKey,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([admin])
-        .rpc();
+      // Admin ya es miembro (Admin) desde la creación: no necesita aprobación
       
       // Generar PDAs para membership request
       [membershipRequestPda] = await PublicKey.findProgramAddress(
@@ -1136,10 +1363,7 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
         program.programId
       );
       
-      const [moderationLogPda] = await PublicKey.findProgramAddress(
-        [Buffer.from("moderation_log"), approvalCommunityPda.toBuffer(), admin.publicKey.toBuffer()],
-        program.programId
-      );
+      const moderationLogPda = await moderationLogPdaOf(approvalCommunityPda);
       
       await program.methods
         .approveMembership(adminNotes)
@@ -1215,15 +1439,13 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
         program.programId
       );
       
-      const [moderationLogPda4] = await PublicKey.findProgramAddress(
-        [Buffer.from("moderation_log"), approvalCommunityPda.toBuffer(), admin.publicKey.toBuffer()],
-        program.programId
-      );
+      const moderationLogPda4 = await moderationLogPdaOf(approvalCommunityPda);
       
       await program.methods
         .rejectMembership(adminNotes)
         .accounts({
           membershipRequest: membershipRequestPda4,
+          community: approvalCommunityPda,
           adminMembership: adminApprovalMembershipPda,
           moderationLog: moderationLogPda4,
           admin: admin.publicKey,
@@ -1237,6 +1459,25 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
       expect(request.status).to.deep.equal({ rejected: {} });
       expect(request.adminNotes).to.equal(adminNotes);
       
+      // Reenviar durante el cooldown debe fallar
+      try {
+        await program.methods
+          .requestMembership("Segundo intento")
+          .accounts({
+            membershipRequest: membershipRequestPda4,
+            community: approvalCommunityPda,
+            user: userPda4,
+            requester: user4.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user4])
+          .rpc();
+        
+        expect.fail("Should have failed - request cooldown still active");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("RequestCooldownActive");
+      }
+      
       console.log("✅ Solicitud rechazada exitosamente");
     });
     
@@ -1285,4 +1526,2304 @@ describe("🎯 VOTING SYSTEM - TESTS AVANZADOS", () => {
       }
     });
   });
+
+  describe("🗑️ CANCEL VOTE TESTS", () => {
+    let creator: Keypair;
+    let voter: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      creator = await newUser();
+      voter = await newUser();
+      community = await newCommunity(creator, "Cancel Community");
+      await joinAs(voter, community);
+    });
+
+    it("✅ Should cancel a vote without ballots and refund the fee minus the penalty", async () => {
+      const vote = await newOpinionVote(creator, community);
+      const { feePerVote } = await program.account.vote.fetch(vote);
+      const { cancelPenaltyPercentage, totalVotes } = await program.account.community.fetch(community);
+      const vaultBefore = await provider.connection.getBalance(vaultPdaOf(community));
+
+      await program.methods
+        .cancelVote()
+        .accounts({
+          vote,
+          community,
+          vault: vaultPdaOf(community),
+          creator: creator.publicKey,
+          feePool: feePoolPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const penalty = Math.floor((feePerVote.toNumber() * cancelPenaltyPercentage) / 100);
+      const vaultAfter = await provider.connection.getBalance(vaultPdaOf(community));
+      expect(vaultBefore - vaultAfter).to.equal(feePerVote.toNumber() - penalty);
+
+      const communityAccount = await program.account.community.fetch(community);
+      expect(communityAccount.totalVotes.toNumber()).to.equal(totalVotes.toNumber() - 1);
+      expect(await provider.connection.getAccountInfo(vote)).to.be.null;
+      console.log(`✅ Vote cancelled, refunded ${vaultBefore - vaultAfter} lamports (penalty ${penalty})`);
+    });
+
+    it("✅ Should let voters reclaim their ballots after a grace-window cancel", async () => {
+      // Mismo creator: el vote anterior ya se cerró y el PDA vuelve a estar libre
+      const vote = await newOpinionVote(creator, community);
+      const participation = await castVoteAs(voter, vote, community);
+
+      await program.methods
+        .cancelVote()
+        .accounts({
+          vote,
+          community,
+          vault: vaultPdaOf(community),
+          creator: creator.publicKey,
+          feePool: feePoolPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      // Con votos el account sigue abierto hasta que los votantes recuperen su rent
+      const cancelled = await program.account.vote.fetch(vote);
+      expect(cancelled.status).to.deep.equal({ cancelled: {} });
+      expect(cancelled.totalVotes.toNumber()).to.equal(1);
+
+      const rent = await provider.connection.getBalance(participation);
+      const voterBefore = await provider.connection.getBalance(voter.publicKey);
+
+      await program.methods
+        .reclaimCancelledBallot()
+        .accounts({
+          participation,
+          vote,
+          user: userPdaOf(voter.publicKey),
+          wallet: voter.publicKey,
+        })
+        .signers([voter])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(participation)).to.be.null;
+      expect(await provider.connection.getBalance(voter.publicKey)).to.be.greaterThan(voterBefore + rent - 10_000);
+
+      await program.methods
+        .closeVote()
+        .accounts({ vote, creator: creator.publicKey })
+        .signers([creator])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(vote)).to.be.null;
+      console.log("✅ Ballot reclaimed and cancelled vote closed");
+    });
+
+    it("❌ Should not let voters reclaim ballots from an active vote", async () => {
+      const vote = await newOpinionVote(creator, community);
+      const participation = await castVoteAs(voter, vote, community);
+
+      await expectError(
+        program.methods
+          .reclaimCancelledBallot()
+          .accounts({
+            participation,
+            vote,
+            user: userPdaOf(voter.publicKey),
+            wallet: voter.publicKey,
+          })
+          .signers([voter])
+          .rpc(),
+        "VoteNotCancelled"
+      );
+      console.log("✅ Reclaim on active vote correctly rejected");
+    });
+
+    it("❌ Should fail if someone other than the creator cancels", async () => {
+      const vote = votePdaOf(community, creator.publicKey);
+
+      await expectError(
+        program.methods
+          .cancelVote()
+          .accounts({
+            vote,
+            community,
+            vault: vaultPdaOf(community),
+            creator: voter.publicKey,
+            feePool: feePoolPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter])
+          .rpc(),
+        "InsufficientPermissions"
+      );
+      console.log("✅ Non-creator cancel correctly rejected");
+    });
+  });
+
+  describe("⚖️ VOTE SETTINGS TESTS", () => {
+    let creator: Keypair;
+    let voter: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      creator = await newUser();
+      voter = await newUser();
+      community = await newCommunity(creator, "Settings Community");
+      await joinAs(voter, community);
+    });
+
+    it("✅ Should store weighting for confidence votes and tally opinion ballots by count", async () => {
+      const vote = await newOpinionVote(creator, community, voteSettings({ weightedVotingEnabled: true }));
+      await castVoteAs(voter, vote, community, 1);
+
+      const voteAccount = await program.account.vote.fetch(vote);
+      expect(voteAccount.weightedVotingEnabled).to.be.true;
+      expect(voteAccount.weightedResults).to.be.empty;
+      expect(voteAccount.results.map((r) => r.toNumber())).to.deep.equal([0, 1]);
+      console.log("✅ Opinion results counted per ballot, weighting kept for confidence votes");
+    });
+
+    it("❌ Should reject a speed bonus on opinion votes", async () => {
+      const otherCreator = await newUser();
+      await joinAs(otherCreator, community);
+
+      await expectError(
+        newOpinionVote(otherCreator, community, voteSettings({ speedBonus: { maxBonus: 5, minBonus: 1 } })),
+        "InvalidSpeedBonus"
+      );
+      console.log("✅ Speed bonus on opinion vote correctly rejected");
+    });
+  });
+
+  describe("⚙️ UPDATE COMMUNITY TESTS", () => {
+    let admin: Keypair;
+    let member: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      admin = await newUser();
+      member = await newUser();
+      community = await newCommunity(admin, "Update Community");
+      await joinAs(member, community);
+    });
+
+    const updateCommunity = async (signer: Keypair, settings: ReturnType<typeof communitySettings>) =>
+      program.methods
+        .updateCommunity(settings, "Adjust community rules")
+        .accounts({
+          community,
+          adminMembership: membershipPdaOf(community, signer.publicKey),
+          moderationLog: await moderationLogPdaOf(community),
+          admin: signer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+
+    it("✅ Should let the same admin update the community more than once", async () => {
+      await updateCommunity(admin, communitySettings({ quorumPercentage: 60 }));
+      await updateCommunity(admin, communitySettings({ cancelPenaltyPercentage: 20 }));
+
+      const communityAccount = await program.account.community.fetch(community);
+      expect(communityAccount.quorumPercentage).to.equal(60);
+      expect(communityAccount.cancelPenaltyPercentage).to.equal(20);
+      expect(communityAccount.moderationLogCount.toNumber()).to.equal(2);
+      console.log("✅ Two updates by the same admin, each with its own moderation log");
+    });
+
+    it("❌ Should fail if a regular member updates the community", async () => {
+      await expectError(
+        updateCommunity(member, communitySettings({ quorumPercentage: 90 })),
+        "InsufficientPermissions"
+      );
+      console.log("✅ Non-admin update correctly rejected");
+    });
+  });
+
+  describe("🛡️ MODERATOR ROLE TESTS", () => {
+    let admin: Keypair;
+    let member: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      admin = await newUser();
+      member = await newUser();
+      community = await newCommunity(admin, "Moderator Roles");
+      await joinAs(member, community);
+    });
+
+    const moderatorAccounts = async (target: Keypair) => ({
+      membership: membershipPdaOf(community, target.publicKey),
+      community,
+      adminMembership: membershipPdaOf(community, admin.publicKey),
+      moderationLog: await moderationLogPdaOf(community),
+      admin: admin.publicKey,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("✅ Should let the same admin assign and then remove a moderator", async () => {
+      await program.methods
+        .assignModerator()
+        .accounts(await moderatorAccounts(member))
+        .signers([admin])
+        .rpc();
+
+      let membership = await program.account.membership.fetch(membershipPdaOf(community, member.publicKey));
+      expect(membership.role).to.deep.equal({ moderator: {} });
+
+      await program.methods
+        .removeModerator("Rotating moderators")
+        .accounts(await moderatorAccounts(member))
+        .signers([admin])
+        .rpc();
+
+      membership = await program.account.membership.fetch(membershipPdaOf(community, member.publicKey));
+      expect(membership.role).to.deep.equal({ member: {} });
+
+      const communityAccount = await program.account.community.fetch(community);
+      expect(communityAccount.moderators.map((m) => m.toString())).to.not.include(member.publicKey.toString());
+      expect(communityAccount.moderationLogCount.toNumber()).to.equal(2);
+      console.log("✅ Assign + remove by the same admin, each with its own moderation log");
+    });
+
+    it("❌ Should fail to remove a member who is not a moderator", async () => {
+      await expectError(
+        program.methods
+          .removeModerator("Not a moderator")
+          .accounts(await moderatorAccounts(member))
+          .signers([admin])
+          .rpc(),
+        "NotModerator"
+      );
+      console.log("✅ Removing a non-moderator correctly rejected");
+    });
+  });
+
+  describe("🏛️ ADMIN COUNCIL TESTS", () => {
+    let authority: Keypair;
+    let secondAdmin: Keypair;
+    let thirdAdmin: Keypair;
+    let fourthAdmin: Keypair;
+    let community: PublicKey;
+    let council: PublicKey;
+
+    const setAdmin = async (wallet: Keypair, promote: boolean) => {
+      const method = promote ? program.methods.promoteAdmin() : program.methods.demoteAdmin();
+      await method
+        .accounts({
+          community,
+          membership: membershipPdaOf(community, wallet.publicKey),
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    };
+
+    type CouncilAction = Parameters<typeof program.methods.proposeCouncilAction>[0];
+
+    const propose = async (proposer: Keypair, action: CouncilAction) => {
+      const { actionNonce } = await program.account.adminCouncil.fetch(council);
+      const pendingAction = pendingActionPdaOf(council, actionNonce);
+
+      await program.methods
+        .proposeCouncilAction(action)
+        .accounts({
+          pendingAction,
+          council,
+          proposerMembership: membershipPdaOf(community, proposer.publicKey),
+          proposer: proposer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc();
+
+      return pendingAction;
+    };
+
+    const leave = (wallet: Keypair) =>
+      program.methods
+        .leaveCommunity()
+        .accounts({
+          membership: membershipPdaOf(community, wallet.publicKey),
+          community,
+          member: wallet.publicKey,
+        })
+        .signers([wallet])
+        .rpc();
+
+    const prune = (member: PublicKey) =>
+      program.methods
+        .removeStaleCouncilMember(member)
+        .accounts({
+          council,
+          community,
+          memberMembership: membershipPdaOf(community, member),
+        })
+        .rpc();
+
+    const approve = (member: Keypair, pendingAction: PublicKey) =>
+      program.methods
+        .approveCouncilAction()
+        .accounts({
+          pendingAction,
+          council,
+          memberMembership: membershipPdaOf(community, member.publicKey),
+          member: member.publicKey,
+        })
+        .signers([member])
+        .rpc();
+
+    before(async () => {
+      authority = await newUser();
+      secondAdmin = await newUser();
+      thirdAdmin = await newUser();
+      fourthAdmin = await newUser();
+      community = await newCommunity(authority, "Council Rotation");
+      council = councilPdaOf(community);
+
+      for (const admin of [secondAdmin, thirdAdmin, fourthAdmin]) {
+        await joinAs(admin, community);
+        await setAdmin(admin, true);
+      }
+
+      const members = [authority, secondAdmin, thirdAdmin];
+      await program.methods
+        .createAdminCouncil(members.map((m) => m.publicKey), 2)
+        .accounts({
+          council,
+          community,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(members.map((m) => ({
+          pubkey: membershipPdaOf(community, m.publicKey),
+          isWritable: false,
+          isSigner: false,
+        })))
+        .signers([authority])
+        .rpc();
+    });
+
+    it("✅ Should rotate a council member through an approved ReplaceMember action", async () => {
+      const pendingAction = await propose(authority, {
+        replaceMember: { oldMember: secondAdmin.publicKey, newMember: fourthAdmin.publicKey },
+      });
+      await approve(thirdAdmin, pendingAction);
+
+      await program.methods
+        .executeCouncilAction()
+        .accounts({
+          pendingAction,
+          council,
+          community,
+          vault: vaultPdaOf(community),
+          proposer: authority.publicKey,
+          recipient: null,
+          newMemberMembership: membershipPdaOf(community, fourthAdmin.publicKey),
+          executor: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      const councilAccount = await program.account.adminCouncil.fetch(council);
+      const members = councilAccount.members.map((m) => m.toString());
+      expect(members).to.include(fourthAdmin.publicKey.toString());
+      expect(members).to.not.include(secondAdmin.publicKey.toString());
+      console.log("✅ Council member rotated");
+    });
+
+    it("❌ Should fail if the authority alone demotes a council admin", async () => {
+      await expectError(setAdmin(thirdAdmin, false), "CouncilApprovalRequired");
+      console.log("✅ Demotion without the council correctly rejected");
+    });
+
+    it("❌ Should reject approvals from a council member who left the community", async () => {
+      await leave(thirdAdmin);
+      const pendingAction = await propose(authority, {
+        transferAuthority: { newAuthority: fourthAdmin.publicKey },
+      });
+
+      await expectError(approve(thirdAdmin, pendingAction), "AccountNotInitialized");
+      console.log("✅ Former member can no longer approve");
+    });
+
+    it("✅ Should prune a departed council member without lowering the threshold", async () => {
+      await expectError(prune(authority.publicKey), "CouncilMemberStillActive");
+      await prune(thirdAdmin.publicKey);
+
+      const councilAccount = await program.account.adminCouncil.fetch(council);
+      expect(councilAccount.members.map((m) => m.toString())).to.deep.equal([
+        authority.publicKey.toString(),
+        fourthAdmin.publicKey.toString(),
+      ]);
+      expect(councilAccount.threshold).to.equal(2);
+      console.log("✅ Stale member pruned, threshold kept");
+    });
+
+    it("❌ Should fail to prune a member if the threshold would become unreachable", async () => {
+      await leave(fourthAdmin);
+
+      await expectError(prune(fourthAdmin.publicKey), "InvalidCouncilThreshold");
+      console.log("✅ Pruning below the threshold correctly rejected");
+    });
+  });
+
+  describe("🏷️ COMMUNITY ROLE TESTS", () => {
+    const PERMISSION_WITHDRAW = 1 << 4;
+
+    let authority: Keypair;
+    let treasurer: Keypair;
+    let plainAdmin: Keypair;
+    let community: PublicKey;
+    let vault: PublicKey;
+    let treasurerRole: PublicKey;
+
+    const withdrawAs = (wallet: Keypair, customRole: PublicKey | null) =>
+      program.methods
+        .withdrawFees(new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          community,
+          vault,
+          adminMembership: membershipPdaOf(community, wallet.publicKey),
+          customRole,
+          admin: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+
+    before(async () => {
+      authority = await newUser();
+      treasurer = await newUser();
+      plainAdmin = await newUser();
+      community = await newCommunity(authority, "Role Treasury");
+      vault = vaultPdaOf(community);
+
+      await joinAs(treasurer, community);
+      await joinAs(plainAdmin, community);
+      await program.methods
+        .promoteAdmin()
+        .accounts({
+          community,
+          membership: membershipPdaOf(community, plainAdmin.publicKey),
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      // Fees en el vault para poder retirar
+      await provider.sendAndConfirm(
+        new Transaction().add(SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: vault,
+          lamports: LAMPORTS_PER_SOL,
+        })),
+        [authority]
+      );
+
+      treasurerRole = PublicKey.findProgramAddressSync(
+        [Buffer.from("community_role"), community.toBuffer(), Buffer.from("treasurer")],
+        program.programId
+      )[0];
+
+      await program.methods
+        .createCommunityRole("treasurer", PERMISSION_WITHDRAW)
+        .accounts({
+          role: treasurerRole,
+          community,
+          adminMembership: membershipPdaOf(community, authority.publicKey),
+          admin: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      await program.methods
+        .assignCommunityRole()
+        .accounts({
+          membership: membershipPdaOf(community, treasurer.publicKey),
+          role: treasurerRole,
+          community,
+          adminMembership: membershipPdaOf(community, authority.publicKey),
+          admin: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    });
+
+    it("✅ Should let a treasurer role withdraw fees", async () => {
+      const before = await provider.connection.getBalance(vault);
+      await withdrawAs(treasurer, treasurerRole);
+
+      expect(await provider.connection.getBalance(vault)).to.equal(before - LAMPORTS_PER_SOL / 10);
+      console.log("✅ Treasurer withdrew fees through the custom role");
+    });
+
+    it("❌ Should fail if a plain admin (not authority) withdraws fees", async () => {
+      await expectError(withdrawAs(plainAdmin, null), "InsufficientPermissions");
+      console.log("✅ Plain admin withdraw correctly rejected");
+    });
+
+    it("✅ Should clear a deleted role on the member's next use", async () => {
+      await program.methods
+        .deleteCommunityRole()
+        .accounts({
+          role: treasurerRole,
+          community,
+          adminMembership: membershipPdaOf(community, authority.publicKey),
+          admin: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      // El miembro sigue pudiendo crear votaciones con sus permisos base, y el rol obsoleto se limpia
+      await program.methods
+        .createVoting("Stale role?", ["Yes", "No"], { opinion: {} }, null, 24, voteSettings())
+        .accounts({
+          vote: votePdaOf(community, treasurer.publicKey),
+          community,
+          membership: membershipPdaOf(community, treasurer.publicKey),
+          customRole: treasurerRole,
+          user: userPdaOf(treasurer.publicKey),
+          creator: treasurer.publicKey,
+          feePool: feePoolPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([treasurer])
+        .rpc();
+
+      const membership = await program.account.membership.fetch(membershipPdaOf(community, treasurer.publicKey));
+      expect(membership.customRole).to.be.null;
+
+      await expectError(withdrawAs(treasurer, null), "InsufficientPermissions");
+      console.log("✅ Deleted role cleared and its permissions revoked");
+    });
+  });
+
+  describe("🌳 SUB-COMMUNITY & FEDERATION TESTS", () => {
+    let authority: Keypair;
+    let parentMember: Keypair;
+    let childMember: Keypair;
+    let parent: PublicKey;
+    let child: PublicKey;
+
+    before(async () => {
+      authority = await newUser();
+      parentMember = await newUser();
+      childMember = await newUser();
+      parent = await newCommunity(authority, "Federation HQ");
+      await joinAs(parentMember, parent);
+
+      // Sub-comunidad creada por el admin de la padre
+      const name = "Federation Chapter";
+      child = PublicKey.findProgramAddressSync(
+        [Buffer.from("community"), authority.publicKey.toBuffer(), Buffer.from(name)],
+        program.programId
+      )[0];
+
+      await program.methods
+        .createCommunity(name, 1, 50, false, { public: {} })
+        .accounts({
+          community: child,
+          feePool: feePoolPda,
+          parent,
+          parentAdminMembership: membershipPdaOf(parent, authority.publicKey),
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      await joinAs(childMember, child);
+    });
+
+    const syncAs = (admin: Keypair, community: PublicKey, parentCommunity: PublicKey) =>
+      program.methods
+        .syncParentRules()
+        .accounts({
+          community,
+          parent: parentCommunity,
+          adminMembership: membershipPdaOf(parentCommunity, admin.publicKey),
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    it("✅ Should keep inherited rules as a snapshot until synced with the parent", async () => {
+      await program.methods
+        .updateCommunity(communitySettings({ requestCooldown: new anchor.BN(3600) }), "Shorter cooldown")
+        .accounts({
+          community: parent,
+          adminMembership: membershipPdaOf(parent, authority.publicKey),
+          moderationLog: await moderationLogPdaOf(parent),
+          admin: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      let childAccount = await program.account.community.fetch(child);
+      expect(childAccount.requestCooldown.toNumber()).to.not.equal(3600);
+
+      await syncAs(authority, child, parent);
+
+      childAccount = await program.account.community.fetch(child);
+      expect(childAccount.requestCooldown.toNumber()).to.equal(3600);
+      console.log("✅ Parent rules propagated with sync_parent_rules");
+    });
+
+    it("❌ Should fail to sync a community that is not a child of the given parent", async () => {
+      await expectError(syncAs(authority, parent, parent), "InvalidParentCommunity");
+      console.log("✅ Sync against a non-parent correctly rejected");
+    });
+
+    it("✅ Should not auto-complete a federated vote before the federation quorum", async () => {
+      // 100% de la federación (2 en la padre + 2 en la hija); quorum absoluto de 1 voto
+      const vote = await newOpinionVote(authority, parent, voteSettings({
+        quorumRequired: new anchor.BN(1),
+        usePercentageQuorum: true,
+        quorumPercentage: 100,
+      }));
+
+      await program.methods
+        .federateVoting()
+        .accounts({ vote, community: parent, creator: authority.publicKey })
+        .remainingAccounts([{ pubkey: child, isWritable: false, isSigner: false }])
+        .signers([authority])
+        .rpc();
+
+      await castVoteAs(authority, vote, parent);
+      await castVoteAs(parentMember, vote, parent);
+
+      const voteAccount = await program.account.vote.fetch(vote);
+      expect(voteAccount.federatedMembers.toNumber()).to.equal(4);
+      expect(voteAccount.status).to.deep.equal({ active: {} });
+      console.log("✅ Federated vote stays open until the federation quorum");
+    });
+
+    it("❌ Should fail to federate a vote without every sub-community", async () => {
+      const vote = await newOpinionVote(parentMember, parent);
+
+      await expectError(
+        program.methods
+          .federateVoting()
+          .accounts({ vote, community: parent, creator: parentMember.publicKey })
+          .signers([parentMember])
+          .rpc(),
+        "InvalidFederation"
+      );
+      console.log("✅ Incomplete federation correctly rejected");
+    });
+  });
+
+  describe("🔑 SESSION KEY TESTS", () => {
+    const SCOPE_CAST_VOTE = 1 << 0;
+    const SCOPE_VOTE_CONFIDENCE = 1 << 1;
+
+    let creator: Keypair;
+    let member: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      creator = await newUser();
+      member = await newUser();
+      community = await newCommunity(creator, "Session Keys");
+      await joinAs(member, community);
+    });
+
+    // Session key efímera fondeada por la wallet para pagar el rent de sus Participation
+    const newSession = async (scopes: number) => {
+      const sessionKey = Keypair.generate();
+      const session = PublicKey.findProgramAddressSync(
+        [Buffer.from("session"), community.toBuffer(), member.publicKey.toBuffer(), sessionKey.publicKey.toBuffer()],
+        program.programId
+      )[0];
+
+      await program.methods
+        .createSessionKey(sessionKey.publicKey, scopes, 1, 5, new anchor.BN(LAMPORTS_PER_SOL / 20))
+        .accounts({
+          session,
+          community,
+          membership: membershipPdaOf(community, member.publicKey),
+          sessionSigner: sessionKey.publicKey,
+          authority: member.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+
+      return { sessionKey, session };
+    };
+
+    const castWithSession = (vote: PublicKey, sessionKey: Keypair, session: PublicKey) =>
+      program.methods
+        .castVote(0)
+        .accounts({
+          participation: PublicKey.findProgramAddressSync(
+            [Buffer.from("participation"), vote.toBuffer(), member.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          vote,
+          membership: membershipPdaOf(community, member.publicKey),
+          user: userPdaOf(member.publicKey),
+          voter: sessionKey.publicKey,
+          session,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([sessionKey])
+        .rpc();
+
+    it("✅ Should cast a vote on behalf of the wallet with a session key", async () => {
+      const vote = await newOpinionVote(creator, community);
+      const { sessionKey, session } = await newSession(SCOPE_CAST_VOTE);
+
+      await castWithSession(vote, sessionKey, session);
+
+      const voteAccount = await program.account.vote.fetch(vote);
+      expect(voteAccount.participants.map((p) => p.toString())).to.include(member.publicKey.toString());
+      const sessionAccount = await program.account.sessionKey.fetch(session);
+      expect(sessionAccount.uses).to.equal(1);
+      console.log("✅ Vote signed by the session key and counted for the wallet");
+    });
+
+    it("❌ Should fail if the session key lacks the cast_vote scope", async () => {
+      const { sessionKey, session } = await newSession(SCOPE_VOTE_CONFIDENCE);
+      const otherCreator = await newUser();
+      await joinAs(otherCreator, community);
+      const vote = await newOpinionVote(otherCreator, community);
+
+      await expectError(castWithSession(vote, sessionKey, session), "SessionScopeNotAllowed");
+      console.log("✅ Out-of-scope session key correctly rejected");
+    });
+  });
+
+  describe("🔑 AUTHORITY TRANSFER TESTS", () => {
+    let authority: Keypair;
+    let successor: Keypair;
+    let bystander: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      authority = await newUser();
+      successor = await newUser();
+      bystander = await newUser();
+      community = await newCommunity(authority, "Authority Handover");
+      await joinAs(successor, community);
+      await joinAs(bystander, community);
+
+      await program.methods
+        .proposeAuthorityTransfer()
+        .accounts({
+          community,
+          newAuthorityMembership: membershipPdaOf(community, successor.publicKey),
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    });
+
+    const acceptAs = (wallet: Keypair) =>
+      program.methods
+        .acceptAuthorityTransfer()
+        .accounts({
+          community,
+          newAuthorityMembership: membershipPdaOf(community, wallet.publicKey),
+          newAuthority: wallet.publicKey,
+        })
+        .signers([wallet])
+        .rpc();
+
+    it("❌ Should fail if someone other than the proposed authority accepts", async () => {
+      await expectError(acceptAs(bystander), "NoPendingAuthorityTransfer");
+      console.log("✅ Accept by a non-proposed member correctly rejected");
+    });
+
+    it("✅ Should hand over authority and keep both wallets as admins", async () => {
+      await acceptAs(successor);
+
+      const communityAccount = await program.account.community.fetch(community);
+      expect(communityAccount.authority.toString()).to.equal(successor.publicKey.toString());
+      expect(communityAccount.pendingAuthority).to.be.null;
+      expect(communityAccount.adminCount).to.equal(2);
+
+      const membership = await program.account.membership.fetch(membershipPdaOf(community, successor.publicKey));
+      expect(membership.role).to.deep.equal({ admin: {} });
+      console.log("✅ Authority transferred, previous authority remains admin");
+    });
+  });
+
+  describe("📦 ARCHIVE & CLOSE COMMUNITY TESTS", () => {
+    let authority: Keypair;
+    let member: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      authority = await newUser();
+      member = await newUser();
+      community = await newCommunity(authority, "Sunset Community");
+      await joinAs(member, community);
+    });
+
+    const closeCommunity = () =>
+      program.methods
+        .closeCommunity()
+        .accounts({ community, parent: null, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+    const closeMembership = (wallet: Keypair, closer: Keypair) =>
+      program.methods
+        .closeMembership()
+        .accounts({
+          community,
+          membership: membershipPdaOf(community, wallet.publicKey),
+          member: wallet.publicKey,
+          closer: closer.publicKey,
+        })
+        .signers([closer])
+        .rpc();
+
+    it("❌ Should fail to close a community that is not archived", async () => {
+      await expectError(closeCommunity(), "CommunityNotArchived");
+      console.log("✅ Closing an active community correctly rejected");
+    });
+
+    it("✅ Should archive, close every membership and reclaim the community rent", async () => {
+      await program.methods
+        .archiveCommunity()
+        .accounts({
+          community,
+          vault: vaultPdaOf(community),
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(vaultPdaOf(community))).to.be.null;
+
+      // Con miembros pendientes todavía no se puede cerrar
+      await closeMembership(member, member);
+      await expectError(closeCommunity(), "CommunityHasMembers");
+
+      // La authority limpia su propia membership y cierra la comunidad
+      await closeMembership(authority, authority);
+      await closeCommunity();
+
+      expect(await provider.connection.getAccountInfo(community)).to.be.null;
+      expect(await provider.connection.getAccountInfo(membershipPdaOf(community, member.publicKey))).to.be.null;
+      console.log("✅ Community archived and fully closed");
+    });
+
+    it("✅ Should archive a community with a council through an approved Archive action", async () => {
+      const councilCommunity = await newCommunity(authority, "Council Sunset");
+      const council = councilPdaOf(councilCommunity);
+
+      await program.methods
+        .createAdminCouncil([authority.publicKey], 1)
+        .accounts({
+          council,
+          community: councilCommunity,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{
+          pubkey: membershipPdaOf(councilCommunity, authority.publicKey),
+          isWritable: false,
+          isSigner: false,
+        }])
+        .signers([authority])
+        .rpc();
+
+      // Sin el consejo no se puede archivar
+      await expectError(
+        program.methods
+          .archiveCommunity()
+          .accounts({
+            community: councilCommunity,
+            vault: vaultPdaOf(councilCommunity),
+            authority: authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc(),
+        "CouncilApprovalRequired"
+      );
+
+      const pendingAction = pendingActionPdaOf(council, new anchor.BN(0));
+      await program.methods
+        .proposeCouncilAction({ archive: {} })
+        .accounts({
+          pendingAction,
+          council,
+          proposerMembership: membershipPdaOf(councilCommunity, authority.publicKey),
+          proposer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      await program.methods
+        .executeCouncilAction()
+        .accounts({
+          pendingAction,
+          council,
+          community: councilCommunity,
+          vault: vaultPdaOf(councilCommunity),
+          proposer: authority.publicKey,
+          recipient: authority.publicKey,
+          newMemberMembership: null,
+          executor: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      const communityAccount = await program.account.community.fetch(councilCommunity);
+      expect(communityAccount.isActive).to.be.false;
+      expect(communityAccount.archivedAt).to.not.be.null;
+      console.log("✅ Community archived through the council");
+    });
+
+    it("✅ Should let a creator cancel an open vote after the community was archived", async () => {
+      const archivedCommunity = await newCommunity(authority, "Archived With Open Vote");
+      const creator = await newUser();
+      await joinAs(creator, archivedCommunity);
+      const vote = await newOpinionVote(creator, archivedCommunity);
+
+      await program.methods
+        .archiveCommunity()
+        .accounts({
+          community: archivedCommunity,
+          vault: vaultPdaOf(archivedCommunity),
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      // El vault ya no existe: la cancelación no reembolsa nada pero no falla
+      await program.methods
+        .cancelVote()
+        .accounts({
+          vote,
+          community: archivedCommunity,
+          vault: vaultPdaOf(archivedCommunity),
+          creator: creator.publicKey,
+          feePool: feePoolPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(vote)).to.be.null;
+      console.log("✅ Open vote cancelled without a refund after archiving");
+    });
+  });
+
+  describe("🖼️ COMMUNITY PROFILE TESTS", () => {
+    let admin: Keypair;
+    let community: PublicKey;
+    let profile: PublicKey;
+
+    before(async () => {
+      admin = await newUser();
+      community = await newCommunity(admin, "Profiled Community");
+      profile = PublicKey.findProgramAddressSync(
+        [Buffer.from("community_profile"), community.toBuffer()],
+        program.programId
+      )[0];
+    });
+
+    const profileData = (overrides: Record<string, unknown> = {}) => ({
+      description: "Local chapter for weekly quiz nights",
+      avatarUri: "https://example.org/avatar.png",
+      bannerUri: null,
+      websiteUri: "https://example.org",
+      socialLinks: ["https://x.com/example"],
+      rulesUri: null,
+      rulesHash: null,
+      ...overrides,
+    });
+
+    const setProfile = (data: ReturnType<typeof profileData>) =>
+      program.methods
+        .setCommunityProfile(data)
+        .accounts({
+          profile,
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+    it("✅ Should create and then replace the community profile", async () => {
+      await setProfile(profileData());
+      await setProfile(profileData({ description: "Weekly quiz nights and tournaments" }));
+
+      const profileAccount = await program.account.communityProfile.fetch(profile);
+      expect(profileAccount.description).to.equal("Weekly quiz nights and tournaments");
+      expect(profileAccount.websiteUri).to.equal("https://example.org");
+      expect(profileAccount.socialLinks).to.deep.equal(["https://x.com/example"]);
+      console.log("✅ Community profile stored on-chain");
+    });
+
+    it("❌ Should fail if the rules URI has no verification hash", async () => {
+      await expectError(setProfile(profileData({ rulesUri: "ipfs://bafyrules" })), "MissingRulesHash");
+      console.log("✅ Unverifiable rules URI correctly rejected");
+    });
+  });
+
+  describe("🏷️ COMMUNITY TIER TESTS", () => {
+    let authority: Keypair;
+
+    before(async () => {
+      authority = await newUser(5);
+    });
+
+    type CommunityTier = Parameters<typeof program.methods.createCommunity>[4];
+
+    const createTiered = async (name: string, tier: CommunityTier) => {
+      const community = PublicKey.findProgramAddressSync(
+        [Buffer.from("community"), authority.publicKey.toBuffer(), Buffer.from(name)],
+        program.programId
+      )[0];
+
+      await program.methods
+        .createCommunity(name, 1, 50, false, tier)
+        .accounts({
+          community,
+          feePool: feePoolPda,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      return community;
+    };
+
+    it("✅ Should charge the Private tier fee and force approval", async () => {
+      const feePoolBefore = await provider.connection.getBalance(feePoolPda);
+      const community = await createTiered("Private Tier", { private: {} });
+
+      expect((await provider.connection.getBalance(feePoolPda)) - feePoolBefore).to.equal(200_000_000);
+      const communityAccount = await program.account.community.fetch(community);
+      expect(communityAccount.tier).to.deep.equal({ private: {} });
+      expect(communityAccount.requiresApproval).to.be.true;
+      console.log("✅ Private tier charged 0.2 SOL and requires approval");
+    });
+
+    it("❌ Should fail to create custom categories below the Premium tier", async () => {
+      const community = await createTiered("Public Tier", { public: {} });
+      const name = "Trivia";
+
+      await expectError(
+        program.methods
+          .createCustomCategory(name, "Trivia nights", "#ff0000", "🎲")
+          .accounts({
+            customCategory: PublicKey.findProgramAddressSync(
+              [Buffer.from("custom_category"), community.toBuffer(), Buffer.from(name)],
+              program.programId
+            )[0],
+            community,
+            adminMembership: membershipPdaOf(community, authority.publicKey),
+            admin: authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc(),
+        "PremiumTierRequired"
+      );
+      console.log("✅ Custom category on a Public community correctly rejected");
+    });
+  });
+
+  describe("👋 LEAVE COMMUNITY TESTS", () => {
+    let authority: Keypair;
+    let member: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      authority = await newUser();
+      member = await newUser();
+      community = await newCommunity(authority, "Leavable Community");
+      await joinAs(member, community);
+    });
+
+    const leaveAs = (wallet: Keypair) =>
+      program.methods
+        .leaveCommunity()
+        .accounts({
+          membership: membershipPdaOf(community, wallet.publicKey),
+          community,
+          member: wallet.publicKey,
+        })
+        .signers([wallet])
+        .rpc();
+
+    it("✅ Should let a member leave and reclaim the membership rent", async () => {
+      await leaveAs(member);
+
+      expect(await provider.connection.getAccountInfo(membershipPdaOf(community, member.publicKey))).to.be.null;
+      const communityAccount = await program.account.community.fetch(community);
+      expect(communityAccount.totalMembers.toNumber()).to.equal(1);
+      console.log("✅ Member left and membership closed");
+    });
+
+    it("❌ Should fail if the authority tries to leave", async () => {
+      await expectError(leaveAs(authority), "AuthorityCannotLeave");
+      console.log("✅ Authority leaving correctly rejected");
+    });
+  });
+
+  describe("✉️ INVITE LIST TESTS", () => {
+    let admin: Keypair;
+    let invited: Keypair;
+    let otherInvited: Keypair;
+    let outsider: Keypair;
+    let community: PublicKey;
+    let inviteList: PublicKey;
+
+    // Hojas sha256(wallet) y pares ordenados, igual que InviteList::verify
+    const leafOf = (wallet: PublicKey) => createHash("sha256").update(wallet.toBuffer()).digest();
+    const hashPair = (a: Buffer, b: Buffer) =>
+      createHash("sha256").update(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])).digest();
+
+    before(async () => {
+      admin = await newUser();
+      invited = await newUser();
+      otherInvited = await newUser();
+      outsider = await newUser();
+      community = await newCommunity(admin, "Invite Only Community", true);
+      inviteList = PublicKey.findProgramAddressSync(
+        [Buffer.from("invite_list"), community.toBuffer()],
+        program.programId
+      )[0];
+
+      const root = hashPair(leafOf(invited.publicKey), leafOf(otherInvited.publicKey));
+      await program.methods
+        .setInviteList(Array.from(root), 24)
+        .accounts({
+          inviteList,
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    });
+
+    const joinWithInviteAs = (wallet: Keypair, proof: Buffer[]) =>
+      program.methods
+        .joinWithInvite(proof.map((node) => Array.from(node)))
+        .accounts({
+          membership: membershipPdaOf(community, wallet.publicKey),
+          inviteList,
+          community,
+          vault: vaultPdaOf(community),
+          user: userPdaOf(wallet.publicKey),
+          member: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+
+    it("✅ Should let an invited wallet join without manual approval", async () => {
+      await joinWithInviteAs(invited, [leafOf(otherInvited.publicKey)]);
+
+      const membership = await program.account.membership.fetch(membershipPdaOf(community, invited.publicKey));
+      expect(membership.isActive).to.be.true;
+      const inviteListAccount = await program.account.inviteList.fetch(inviteList);
+      expect(inviteListAccount.totalJoined).to.equal(1);
+      console.log("✅ Invited wallet joined with a Merkle proof");
+    });
+
+    it("❌ Should fail if the wallet is not in the invite list", async () => {
+      await expectError(
+        joinWithInviteAs(outsider, [leafOf(otherInvited.publicKey)]),
+        "InvalidInviteProof"
+      );
+      console.log("✅ Non-invited wallet correctly rejected");
+    });
+
+    it("❌ Should fail to join once the invite list is revoked", async () => {
+      await program.methods
+        .revokeInviteList()
+        .accounts({
+          inviteList,
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      await expectError(
+        joinWithInviteAs(otherInvited, [leafOf(invited.publicKey)]),
+        "InviteListInactive"
+      );
+      console.log("✅ Revoked invite list correctly rejected");
+    });
+  });
+
+  describe("💳 MEMBERSHIP DUES TESTS", () => {
+    const fee = new anchor.BN(LAMPORTS_PER_SOL / 10);
+    const period = new anchor.BN(86_400);
+    let admin: Keypair;
+    let member: Keypair;
+    let community: PublicKey;
+
+    before(async () => {
+      admin = await newUser();
+      member = await newUser();
+      community = await newCommunity(admin, "Dues Community");
+
+      await program.methods
+        .updateCommunity(communitySettings({ membershipFee: fee, membershipPeriod: period }), "Enable dues")
+        .accounts({
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          moderationLog: await moderationLogPdaOf(community),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await joinAs(member, community);
+    });
+
+    const renewAs = (wallet: Keypair, periods: number, target = community) =>
+      program.methods
+        .renewMembership(periods)
+        .accounts({
+          membership: membershipPdaOf(target, wallet.publicKey),
+          community: target,
+          vault: vaultPdaOf(target),
+          member: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+
+    it("✅ Should extend the membership and pay the dues into the vault", async () => {
+      const membership = membershipPdaOf(community, member.publicKey);
+      const { expiresAt } = await program.account.membership.fetch(membership);
+      const vaultBefore = await provider.connection.getBalance(vaultPdaOf(community));
+
+      await renewAs(member, 2);
+
+      const renewed = await program.account.membership.fetch(membership);
+      expect(renewed.expiresAt.sub(expiresAt).toNumber()).to.equal(2 * period.toNumber());
+      const vaultAfter = await provider.connection.getBalance(vaultPdaOf(community));
+      expect(vaultAfter - vaultBefore).to.equal(2 * fee.toNumber());
+      console.log("✅ Membership renewed for two periods");
+    });
+
+    it("❌ Should fail to renew zero periods", async () => {
+      await expectError(renewAs(member, 0), "InvalidRenewalPeriods");
+      console.log("✅ Zero-period renewal correctly rejected");
+    });
+
+    it("❌ Should fail to renew in a community without dues", async () => {
+      const freeCommunity = await newCommunity(admin, "Free Community");
+      await joinAs(member, freeCommunity);
+
+      await expectError(renewAs(member, 1, freeCommunity), "NoMembershipDues");
+      console.log("✅ Renewal without dues correctly rejected");
+    });
+
+    it("❌ Should fail to set a membership fee above the maximum", async () => {
+      await expectError(
+        program.methods
+          .updateCommunity(
+            communitySettings({ membershipFee: new anchor.BN(101).mul(new anchor.BN(LAMPORTS_PER_SOL)) }),
+            "Raise dues"
+          )
+          .accounts({
+            community,
+            adminMembership: membershipPdaOf(community, admin.publicKey),
+            moderationLog: await moderationLogPdaOf(community),
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc(),
+        "InvalidMembershipFee"
+      );
+      console.log("✅ Oversized membership fee correctly rejected");
+    });
+  });
+
+  describe("⏳ MEMBER CAP & WAITLIST TESTS", () => {
+    let admin: Keypair;
+    let member: Keypair;
+    let first: Keypair;
+    let second: Keypair;
+    let community: PublicKey;
+    let waitlist: PublicKey;
+
+    before(async () => {
+      admin = await newUser();
+      member = await newUser();
+      first = await newUser();
+      second = await newUser();
+      community = await newCommunity(admin, "Capped Community");
+      waitlist = PublicKey.findProgramAddressSync(
+        [Buffer.from("waitlist"), community.toBuffer()],
+        program.programId
+      )[0];
+
+      await program.methods
+        .updateCommunity(communitySettings({ maxMembers: new anchor.BN(2) }), "Cap the community")
+        .accounts({
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          moderationLog: await moderationLogPdaOf(community),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await joinAs(member, community);
+    });
+
+    const joinWaitlistAs = (wallet: Keypair) =>
+      program.methods
+        .joinWaitlist()
+        .accounts({
+          waitlist,
+          community,
+          membership: membershipPdaOf(community, wallet.publicKey),
+          user: userPdaOf(wallet.publicKey),
+          member: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+
+    const admit = (candidate: Keypair) =>
+      program.methods
+        .admitFromWaitlist()
+        .accounts({
+          community,
+          waitlist,
+          candidate: candidate.publicKey,
+          membership: membershipPdaOf(community, candidate.publicKey),
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    it("❌ Should fail to join a community that is full", async () => {
+      await expectError(joinAs(first, community), "CommunityFull");
+      console.log("✅ Join over the member cap correctly rejected");
+    });
+
+    it("✅ Should queue users and refund the deposit when leaving the waitlist", async () => {
+      await joinWaitlistAs(first);
+      await joinWaitlistAs(second);
+
+      await program.methods
+        .leaveWaitlist()
+        .accounts({ waitlist, community, member: second.publicKey })
+        .signers([second])
+        .rpc();
+
+      const waitlistAccount = await program.account.waitlist.fetch(waitlist);
+      expect(waitlistAccount.entries.map((entry) => entry.user.toBase58())).to.deep.equal([first.publicKey.toBase58()]);
+      const communityAccount = await program.account.community.fetch(community);
+      expect(communityAccount.waitlistCount).to.equal(1);
+      console.log("✅ Waitlist keeps the queue order");
+    });
+
+    it("❌ Should fail to admit anyone but the first in line", async () => {
+      await joinWaitlistAs(second);
+      await program.methods
+        .leaveCommunity()
+        .accounts({
+          membership: membershipPdaOf(community, member.publicKey),
+          community,
+          member: member.publicKey,
+        })
+        .signers([member])
+        .rpc();
+
+      await expectError(admit(second), "NotOnWaitlist");
+      console.log("✅ Out-of-order admission correctly rejected");
+    });
+
+    it("✅ Should admit the first user in line once a seat opens", async () => {
+      await admit(first);
+
+      const membership = await program.account.membership.fetch(membershipPdaOf(community, first.publicKey));
+      expect(membership.isActive).to.be.true;
+      const waitlistAccount = await program.account.waitlist.fetch(waitlist);
+      expect(waitlistAccount.totalAdmitted).to.equal(1);
+      expect(waitlistAccount.entries.length).to.equal(1);
+      console.log("✅ First user in line admitted");
+    });
+  });
+
+  describe("📝 MEMBERSHIP REQUEST RESUBMISSION TESTS", () => {
+    let admin: Keypair;
+    let applicant: Keypair;
+    let community: PublicKey;
+    let membershipRequest: PublicKey;
+
+    before(async () => {
+      admin = await newUser();
+      applicant = await newUser();
+      community = await newCommunity(admin, "Resubmission Community", true);
+      membershipRequest = PublicKey.findProgramAddressSync(
+        [Buffer.from("membership_request"), community.toBuffer(), applicant.publicKey.toBuffer()],
+        program.programId
+      )[0];
+    });
+
+    const requestMembership = (message: string) =>
+      program.methods
+        .requestMembership(message)
+        .accounts({
+          membershipRequest,
+          community,
+          user: userPdaOf(applicant.publicKey),
+          requester: applicant.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([applicant])
+        .rpc();
+
+    it("✅ Should let the applicant cancel a pending request and submit a new one", async () => {
+      await requestMembership("Please let me in");
+
+      await program.methods
+        .cancelMembershipRequest()
+        .accounts({ membershipRequest, requester: applicant.publicKey })
+        .signers([applicant])
+        .rpc();
+      expect(await provider.connection.getAccountInfo(membershipRequest)).to.be.null;
+
+      await requestMembership("Corrected introduction");
+      const request = await program.account.membershipRequest.fetch(membershipRequest);
+      expect(request.status).to.deep.equal({ pending: {} });
+      expect(request.message).to.equal("Corrected introduction");
+      console.log("✅ Pending request cancelled and resubmitted");
+    });
+
+    it("❌ Should fail to submit a second request while one is pending", async () => {
+      await expectError(requestMembership("Any news?"), "RequestAlreadyPending");
+      console.log("✅ Duplicate pending request correctly rejected");
+    });
+
+    it("❌ Should fail to resubmit a rejected request during the cooldown", async () => {
+      await program.methods
+        .rejectMembership("Not a fit right now")
+        .accounts({
+          membershipRequest,
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          customRole: null,
+          moderationLog: await moderationLogPdaOf(community),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await expectError(requestMembership("Trying again"), "RequestCooldownActive");
+      console.log("✅ Resubmission during the cooldown correctly rejected");
+    });
+
+    it("❌ Should fail to resubmit a request that was already approved", async () => {
+      const member = await newUser();
+      const approvedRequest = PublicKey.findProgramAddressSync(
+        [Buffer.from("membership_request"), community.toBuffer(), member.publicKey.toBuffer()],
+        program.programId
+      )[0];
+      const submit = () =>
+        program.methods
+          .requestMembership("Count me in")
+          .accounts({
+            membershipRequest: approvedRequest,
+            community,
+            user: userPdaOf(member.publicKey),
+            requester: member.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([member])
+          .rpc();
+
+      await submit();
+      await program.methods
+        .approveMembership("Welcome")
+        .accounts({
+          membershipRequest: approvedRequest,
+          membership: membershipPdaOf(community, member.publicKey),
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          customRole: null,
+          moderationLog: await moderationLogPdaOf(community),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await expectError(submit(), "RequestAlreadyApproved");
+      console.log("✅ Resubmitting an approved request correctly rejected");
+    });
+  });
+
+  describe("⏳ WAITLIST ADMISSION RULES TESTS", () => {
+    let admin: Keypair;
+    let community: PublicKey;
+    let waitlist: PublicKey;
+
+    before(async () => {
+      admin = await newUser();
+      community = await newCommunity(admin, "Dues Waitlist Community");
+      waitlist = PublicKey.findProgramAddressSync(
+        [Buffer.from("waitlist"), community.toBuffer()],
+        program.programId
+      )[0];
+
+      await updateCommunity(communitySettings({
+        maxMembers: new anchor.BN(1),
+        membershipFee: new anchor.BN(LAMPORTS_PER_SOL / 10),
+        membershipPeriod: new anchor.BN(86_400),
+      }));
+    });
+
+    const updateCommunity = async (settings: ReturnType<typeof communitySettings>) =>
+      program.methods
+        .updateCommunity(settings, "Adjust admission rules")
+        .accounts({
+          community,
+          adminMembership: membershipPdaOf(community, admin.publicKey),
+          moderationLog: await moderationLogPdaOf(community),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+    const joinWaitlistAs = (wallet: Keypair) =>
+      program.methods
+        .joinWaitlist()
+        .accounts({
+          waitlist,
+          community,
+          membership: membershipPdaOf(community, wallet.publicKey),
+          user: userPdaOf(wallet.publicKey),
+          member: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+
+    const admit = (candidate: Keypair) =>
+      program.methods
+        .admitFromWaitlist()
+        .accounts({
+          community,
+          waitlist,
+          candidate: candidate.publicKey,
+          membership: membershipPdaOf(community, candidate.publicKey),
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    it("✅ Should admit into a dues community already expired until the first renewal", async () => {
+      const waiter = await newUser();
+      await joinWaitlistAs(waiter);
+      await updateCommunity(communitySettings({ maxMembers: new anchor.BN(2) }));
+      await admit(waiter);
+
+      const membership = membershipPdaOf(community, waiter.publicKey);
+      const admitted = await program.account.membership.fetch(membership);
+      expect(admitted.expiresAt.toNumber()).to.equal(admitted.joinedAt.toNumber());
+      await expectError(newOpinionVote(waiter, community), "MembershipExpired");
+
+      await program.methods
+        .renewMembership(1)
+        .accounts({
+          membership,
+          community,
+          vault: vaultPdaOf(community),
+          member: waiter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([waiter])
+        .rpc();
+
+      const renewed = await program.account.membership.fetch(membership);
+      expect(renewed.expiresAt.toNumber()).to.be.greaterThan(admitted.joinedAt.toNumber());
+      console.log("✅ Admitted member paid the first period with renew_membership");
+    });
+
+    it("❌ Should fail to admit from the waitlist once the community requires approval", async () => {
+      const waiter = await newUser();
+      await joinWaitlistAs(waiter);
+      await updateCommunity(communitySettings({ requiresApproval: true, maxMembers: new anchor.BN(3) }));
+
+      await expectError(admit(waiter), "CommunityRequiresApproval");
+      console.log("✅ Waitlist admission bypassing approval correctly rejected");
+    });
+  });
+});
+
+// ============================================================================
+// ⏱️ TESTS CON CONTROL DEL RELOJ (bankrun): deadlines de horas o días
+// ============================================================================
+
+describe("⏱️ VOTING SYSTEM - TESTS CON RELOJ (bankrun)", () => {
+  let context: ProgramTestContext;
+  let program: Program<VotingSystem>;
+  let kit: ReturnType<typeof testKit>;
+
+  // Avanza el reloj del banco para superar deadlines
+  const warp = async (seconds: number) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(
+        clock.slot,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        clock.unixTimestamp + BigInt(seconds)
+      )
+    );
+  };
+
+  const sha256 = (...parts: Buffer[]) =>
+    Array.from(createHash("sha256").update(Buffer.concat(parts)).digest());
+
+  before("🛠️ Setup bankrun", async () => {
+    context = await startAnchor("", [], []);
+    program = new Program<VotingSystem>(IDL as VotingSystem, new BankrunProvider(context));
+    kit = testKit(program, async (wallet, lamports) => {
+      context.setAccount(wallet, {
+        lamports,
+        data: Buffer.alloc(0),
+        owner: SystemProgram.programId,
+        executable: false,
+      });
+    });
+
+    await program.methods
+      .initializeFeePool()
+      .accounts({
+        feePool: kit.feePool,
+        authority: context.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  // Comunidad con admin propio (modera), creator de la pregunta y votantes
+  const knowledgeCommunity = async (name: string, voterCount: number) => {
+    const admin = await kit.newUser();
+    const community = await kit.newCommunity(admin, name);
+    const creator = await kit.newUser();
+    await kit.joinAs(creator, community);
+
+    const voters: Keypair[] = [];
+    for (let i = 0; i < voterCount; i++) {
+      const voter = await kit.newUser();
+      await kit.joinAs(voter, community);
+      voters.push(voter);
+    }
+
+    return { admin, community, creator, voters };
+  };
+
+  type KnowledgeCommunity = Awaited<ReturnType<typeof knowledgeCommunity>>;
+
+  const answerSalt = Buffer.alloc(32, 9);
+  const ballotSalt = (i: number) => Buffer.alloc(32, i + 1);
+
+  // Pregunta Knowledge abierta con la respuesta comprometida como hash(respuesta || salt)
+  const createKnowledgeVote = async (
+    members: KnowledgeCommunity,
+    quorum: number,
+    correctAnswer: number,
+    settings: Record<string, unknown> = {}
+  ) => {
+    const vote = kit.votePdaOf(members.community, members.creator.publicKey);
+
+    await program.methods
+      .createVoting(
+        "What is 2 + 2?",
+        ["3", "4", "5"],
+        { knowledge: {} },
+        sha256(Buffer.from([correctAnswer]), answerSalt),
+        1,
+        kit.voteSettings({ quorumRequired: new anchor.BN(quorum), ...settings })
+      )
+      .accounts({
+        vote,
+        community: members.community,
+        membership: kit.membershipPdaOf(members.community, members.creator.publicKey),
+        user: kit.userPdaOf(members.creator.publicKey),
+        creator: members.creator.publicKey,
+        feePool: kit.feePool,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([members.creator])
+      .rpc();
+
+    return vote;
+  };
+
+  // Votos sellados de cada votante y cierre al deadline (queda AwaitingReveal)
+  const commitAndClose = async (members: KnowledgeCommunity, vote: PublicKey, answers: number[]) => {
+    for (const [i, voter] of members.voters.entries()) {
+      await program.methods
+        .commitVote(sha256(Buffer.from([answers[i]]), ballotSalt(i), voter.publicKey.toBuffer()))
+        .accounts({
+          participation: kit.participationPdaOf(vote, voter.publicKey),
+          vote,
+          membership: kit.membershipPdaOf(members.community, voter.publicKey),
+          user: kit.userPdaOf(voter.publicKey),
+          voter: voter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc();
+    }
+
+    await warp(3_601);
+
+    await program.methods
+      .closeKnowledgeVoting()
+      .accounts({ vote, community: members.community })
+      .rpc();
+  };
+
+  const revealAnswer = (
+    members: KnowledgeCommunity,
+    vote: PublicKey,
+    correctAnswer: number,
+    salt = answerSalt,
+    explanationHash: number[] | null = null,
+    explanationUri: string | null = null
+  ) =>
+    program.methods
+      .revealCorrectAnswer(correctAnswer, Array.from(salt), "Two plus two is four", explanationHash, explanationUri)
+      .accounts({ vote, creator: members.creator.publicKey })
+      .signers([members.creator])
+      .rpc();
+
+  const revealBallots = async (members: KnowledgeCommunity, vote: PublicKey, answers: number[]) => {
+    for (const [i, voter] of members.voters.entries()) {
+      await program.methods
+        .revealBallot(answers[i], Array.from(ballotSalt(i)))
+        .accounts({
+          participation: kit.participationPdaOf(vote, voter.publicKey),
+          vote,
+          revealer: voter.publicKey,
+        })
+        .signers([voter])
+        .rpc();
+    }
+  };
+
+  // Votos sellados, cierre al deadline y revelación de la respuesta y de cada voto
+  const commitAndReveal = async (
+    members: KnowledgeCommunity,
+    vote: PublicKey,
+    answers: number[],
+    correctAnswer: number
+  ) => {
+    await commitAndClose(members, vote, answers);
+    await revealAnswer(members, vote, correctAnswer);
+    await revealBallots(members, vote, answers);
+  };
+
+  // Pregunta Knowledge cerrada y revelada: cada votante ya reveló su voto sellado
+  const knowledgeRound = async (
+    name: string,
+    answers: number[],
+    correctAnswer = 1,
+    settings: Record<string, unknown> = {}
+  ) => {
+    const members = await knowledgeCommunity(name, answers.length);
+    const vote = await createKnowledgeVote(members, answers.length, correctAnswer, settings);
+
+    await commitAndReveal(members, vote, answers, correctAnswer);
+
+    return { ...members, vote };
+  };
+
+  type KnowledgeRound = Awaited<ReturnType<typeof knowledgeRound>>;
+
+  const voteConfidence = (round: KnowledgeRound, voter: Keypair, isConfident: boolean) =>
+    program.methods
+      .voteConfidence(isConfident)
+      .accounts({
+        confidenceParticipation: kit.pda(Buffer.from("confidence"), round.vote.toBuffer(), voter.publicKey.toBuffer()),
+        vote: round.vote,
+        participation: kit.participationPdaOf(round.vote, voter.publicKey),
+        user: kit.userPdaOf(voter.publicKey),
+        membership: kit.membershipPdaOf(round.community, voter.publicKey),
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+
+  const finalizeConfidence = (round: KnowledgeRound) =>
+    program.methods
+      .finalizeConfidenceVoting()
+      .accounts({
+        vote: round.vote,
+        creatorUser: kit.userPdaOf(round.creator.publicKey),
+      })
+      .rpc();
+
+  const claimReward = (round: KnowledgeRound, voter: Keypair) =>
+    program.methods
+      .claimKnowledgeReward()
+      .accounts({
+        participation: kit.participationPdaOf(round.vote, voter.publicKey),
+        vote: round.vote,
+        user: kit.userPdaOf(voter.publicKey),
+        claimer: voter.publicKey,
+      })
+      .signers([voter])
+      .rpc();
+
+  describe("🏁 CONFIDENCE FINALIZATION TESTS", () => {
+    it("✅ Should record a rejected answer when most confidence votes are against", async () => {
+      const round = await knowledgeRound("Rejected Answer", [1, 1, 1]);
+      await voteConfidence(round, round.voters[0], true);
+      await voteConfidence(round, round.voters[1], false);
+      await voteConfidence(round, round.voters[2], false);
+
+      await warp(86_401);
+      await finalizeConfidence(round);
+
+      const vote = await program.account.vote.fetch(round.vote);
+      expect(vote.status).to.deep.equal({ completed: {} });
+      expect(vote.answerValidated).to.be.false;
+      console.log("✅ Answer rejected by the community (1 for, 2 against)");
+    });
+
+    it("✅ Should record a validated answer when most confidence votes are for", async () => {
+      const round = await knowledgeRound("Validated Answer", [1, 1]);
+      await voteConfidence(round, round.voters[0], true);
+      await voteConfidence(round, round.voters[1], true);
+
+      const creatorPda = kit.userPdaOf(round.creator.publicKey);
+      const before = (await program.account.user.fetch(creatorPda)).reputationPoints.toNumber();
+
+      await warp(86_401);
+      await finalizeConfidence(round);
+
+      const vote = await program.account.vote.fetch(round.vote);
+      expect(vote.answerValidated).to.be.true;
+      expect((await program.account.user.fetch(creatorPda)).reputationPoints.toNumber()).to.equal(before + 10);
+      console.log("✅ Answer validated, creator +10 reputation");
+    });
+
+    it("❌ Should fail to finalize before the confidence deadline", async () => {
+      const round = await knowledgeRound("Early Finalize", [1]);
+
+      await kit.expectError(finalizeConfidence(round), "ConfidenceVotingStillActive");
+      console.log("✅ Early finalization correctly rejected");
+    });
+  });
+
+  describe("🎁 KNOWLEDGE REWARD CLAIM TESTS", () => {
+    it("✅ Should pay the knowledge bonus once the answer is validated", async () => {
+      const round = await knowledgeRound("Claim Validated", [1]);
+      await voteConfidence(round, round.voters[0], true);
+      await warp(86_401);
+      await finalizeConfidence(round);
+
+      const userPda = kit.userPdaOf(round.voters[0].publicKey);
+      const before = (await program.account.user.fetch(userPda)).reputationPoints.toNumber();
+      await claimReward(round, round.voters[0]);
+
+      const after = (await program.account.user.fetch(userPda)).reputationPoints.toNumber();
+      expect(after - before).to.equal(3);
+      const participation = await program.account.participation.fetch(
+        kit.participationPdaOf(round.vote, round.voters[0].publicKey)
+      );
+      expect(participation.rewardClaimed).to.be.true;
+      console.log(`✅ Knowledge bonus claimed: ${before} → ${after}`);
+    });
+
+    it("❌ Should not pay the knowledge bonus when the community rejected the answer", async () => {
+      const round = await knowledgeRound("Claim Rejected", [1, 1]);
+      await voteConfidence(round, round.voters[0], false);
+      await voteConfidence(round, round.voters[1], false);
+      await warp(86_401);
+      await finalizeConfidence(round);
+
+      await kit.expectError(claimReward(round, round.voters[0]), "AnswerNotValidated");
+      console.log("✅ Claim on rejected answer correctly blocked");
+    });
+  });
+
+  describe("⚖️ ANSWER DISPUTE TESTS", () => {
+    const disputePdaOf = (vote: PublicKey) => kit.pda(Buffer.from("dispute"), vote.toBuffer());
+
+    const openDispute = (round: KnowledgeRound, challenger: Keypair, proposedAnswer: number) =>
+      program.methods
+        .openAnswerDispute(proposedAnswer, "The correct answer is another option", new anchor.BN(50_000_000))
+        .accounts({
+          dispute: disputePdaOf(round.vote),
+          vote: round.vote,
+          participation: kit.participationPdaOf(round.vote, challenger.publicKey),
+          challenger: challenger.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([challenger])
+        .rpc();
+
+    const closeDispute = (round: KnowledgeRound, challenger: Keypair) =>
+      program.methods
+        .closeAnswerDispute()
+        .accounts({ dispute: disputePdaOf(round.vote), challenger: challenger.publicKey })
+        .signers([challenger])
+        .rpc();
+
+    it("✅ Should pay the creator's answer bond to the challenger and reclaim the dispute rent", async () => {
+      const round = await knowledgeRound("Dispute Overturned", [2, 1]);
+      const challenger = round.voters[0];
+
+      const vote = await program.account.vote.fetch(round.vote);
+      expect(vote.answerBond.toNumber()).to.equal(50_000_000);
+
+      await openDispute(round, challenger, 2);
+
+      // El creator no iguala la fianza: pasado su plazo el admin resuelve
+      await warp(86_401);
+      const challengerBefore = await context.banksClient.getBalance(challenger.publicKey);
+
+      await program.methods
+        .resolveDispute(false, 2)
+        .accounts({
+          dispute: disputePdaOf(round.vote),
+          vote: round.vote,
+          creatorUser: kit.userPdaOf(round.creator.publicKey),
+          creator: round.creator.publicKey,
+          challenger: challenger.publicKey,
+          moderatorMembership: kit.membershipPdaOf(round.community, round.admin.publicKey),
+          moderator: round.admin.publicKey,
+        })
+        .signers([round.admin])
+        .rpc();
+
+      const challengerAfter = await context.banksClient.getBalance(challenger.publicKey);
+      expect(Number(challengerAfter - challengerBefore)).to.equal(50_000_000 + 50_000_000);
+
+      const resolved = await program.account.vote.fetch(round.vote);
+      expect(resolved.correctAnswer).to.equal(2);
+      expect(resolved.answerValidated).to.be.true;
+      expect(resolved.answerBond.toNumber()).to.equal(0);
+
+      // Las fees las paga el wallet del provider: el challenger recibe el rent íntegro
+      const rent = await context.banksClient.getBalance(disputePdaOf(round.vote));
+      await closeDispute(round, challenger);
+
+      expect(await context.banksClient.getAccount(disputePdaOf(round.vote))).to.be.null;
+      expect(Number((await context.banksClient.getBalance(challenger.publicKey)) - challengerAfter))
+        .to.equal(Number(rent));
+      console.log("✅ Challenger won both bonds and reclaimed the dispute rent");
+    });
+
+    it("❌ Should not close a dispute that is still open", async () => {
+      const round = await knowledgeRound("Dispute Open", [2]);
+      await openDispute(round, round.voters[0], 2);
+
+      await kit.expectError(closeDispute(round, round.voters[0]), "DisputeStillOpen");
+      console.log("✅ Closing an open dispute correctly rejected");
+    });
+  });
+
+  describe("🏁 TOURNAMENT SCORING TESTS", () => {
+    // Torneo de una ronda con un participante inscrito; la comunidad valida o rechaza la respuesta
+    const scoredTournamentRound = async (name: string, validated: boolean) => {
+      const members = await knowledgeCommunity(name, 2);
+      const tournament = kit.pda(
+        Buffer.from("tournament"),
+        members.community.toBuffer(),
+        members.creator.publicKey.toBuffer(),
+        Buffer.from("Quiz Night")
+      );
+
+      await program.methods
+        .createTournament("Quiz Night", 1, new anchor.BN(0))
+        .accounts({
+          tournament,
+          community: members.community,
+          membership: kit.membershipPdaOf(members.community, members.creator.publicKey),
+          creator: members.creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([members.creator])
+        .rpc();
+
+      const vote = kit.pda(Buffer.from("tournament_round"), tournament.toBuffer(), Buffer.from([0]));
+      await program.methods
+        .addTournamentRound("What is 2 + 2?", ["3", "4", "5"], sha256(Buffer.from([1]), answerSalt), 1)
+        .accounts({
+          vote,
+          tournament,
+          community: members.community,
+          creator: members.creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([members.creator])
+        .rpc();
+
+      const score = kit.pda(Buffer.from("tournament_score"), tournament.toBuffer(), members.voters[0].publicKey.toBuffer());
+      await program.methods
+        .joinTournament()
+        .accounts({
+          score,
+          tournament,
+          membership: kit.membershipPdaOf(members.community, members.voters[0].publicKey),
+          participant: members.voters[0].publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([members.voters[0]])
+        .rpc();
+
+      await commitAndReveal(members, vote, [1, 1], 1);
+      const round = { ...members, vote };
+      await voteConfidence(round, members.voters[0], validated);
+      await voteConfidence(round, members.voters[1], validated);
+      await warp(86_401);
+      await finalizeConfidence(round);
+
+      const record = () =>
+        program.methods
+          .recordTournamentRound()
+          .accounts({
+            score,
+            tournament,
+            vote,
+            participation: kit.participationPdaOf(vote, members.voters[0].publicKey),
+          })
+          .rpc();
+
+      return { ...members, tournament, score, record };
+    };
+
+    it("✅ Should score a correct answer once the community validated it", async () => {
+      const round = await scoredTournamentRound("Tournament Validated", true);
+      await round.record();
+
+      const score = await program.account.tournamentScore.fetch(round.score);
+      expect(score.correctAnswers).to.equal(1);
+      expect(score.roundsScored).to.equal(1);
+
+      const tournament = await program.account.tournament.fetch(round.tournament);
+      expect(tournament.standings[0].user.toString()).to.equal(round.voters[0].publicKey.toString());
+      console.log("✅ Validated round scored and standings updated");
+    });
+
+    it("❌ Should not score a round whose answer the community rejected", async () => {
+      const round = await scoredTournamentRound("Tournament Rejected", false);
+
+      await kit.expectError(round.record(), "AnswerNotValidated");
+      console.log("✅ Rejected round correctly left unscored");
+    });
+  });
+
+  describe("🔐 KNOWLEDGE COMMIT-REVEAL TESTS", () => {
+    it("✅ Should reveal the committed answer and every sealed ballot", async () => {
+      const round = await knowledgeRound("Commit Reveal", [1, 0]);
+
+      const vote = await program.account.vote.fetch(round.vote);
+      expect(vote.status).to.deep.equal({ confidenceVoting: {} });
+      expect(vote.correctAnswer).to.equal(1);
+      expect(vote.revealedBallots.toNumber()).to.equal(2);
+
+      const participation = await program.account.participation.fetch(
+        kit.participationPdaOf(round.vote, round.voters[1].publicKey)
+      );
+      expect(participation.isRevealed).to.be.true;
+      expect(participation.optionSelected).to.equal(0);
+      console.log("✅ Answer and ballots revealed, confidence phase open");
+    });
+
+    it("❌ Should fail to reveal an answer that does not match the commitment", async () => {
+      const members = await knowledgeCommunity("Wrong Salt", 1);
+      const vote = await createKnowledgeVote(members, 1, 1);
+      await commitAndClose(members, vote, [1]);
+
+      await kit.expectError(revealAnswer(members, vote, 1, Buffer.alloc(32, 7)), "InvalidAnswerHash");
+      console.log("✅ Mismatched answer reveal correctly rejected");
+    });
+  });
+
+  describe("🤝 CONFIDENCE VOTE RECORD TESTS", () => {
+    it("✅ Should store one confidence record per voter", async () => {
+      const round = await knowledgeRound("Confidence Record", [1, 1]);
+      await voteConfidence(round, round.voters[0], true);
+      await voteConfidence(round, round.voters[1], false);
+
+      const record = await program.account.confidenceParticipation.fetch(
+        kit.pda(Buffer.from("confidence"), round.vote.toBuffer(), round.voters[1].publicKey.toBuffer())
+      );
+      expect(record.user.toString()).to.equal(round.voters[1].publicKey.toString());
+      expect(record.isConfident).to.be.false;
+
+      const vote = await program.account.vote.fetch(round.vote);
+      expect(vote.confidenceVotesFor).to.equal(1);
+      expect(vote.confidenceVotesAgainst).to.equal(1);
+      console.log("✅ Confidence records stored per voter");
+    });
+
+    it("❌ Should fail if a member who did not answer votes confidence", async () => {
+      const round = await knowledgeRound("Confidence Outsider", [1]);
+
+      // El creator es miembro pero no tiene Participation en su propia pregunta
+      await kit.expectError(voteConfidence(round, round.creator, true), "AccountNotInitialized");
+      console.log("✅ Confidence vote without an answer correctly rejected");
+    });
+  });
+
+  describe("⚡ SPEED BONUS TESTS", () => {
+    it("✅ Should add the speed bonus to the knowledge reward", async () => {
+      // Bonus constante (max = min) para no depender del instante exacto del commit
+      const round = await knowledgeRound("Speed Bonus", [1], 1, { speedBonus: { maxBonus: 5, minBonus: 5 } });
+      await voteConfidence(round, round.voters[0], true);
+      await warp(86_401);
+      await finalizeConfidence(round);
+
+      const userPda = kit.userPdaOf(round.voters[0].publicKey);
+      const before = (await program.account.user.fetch(userPda)).reputationPoints.toNumber();
+      await claimReward(round, round.voters[0]);
+
+      const after = (await program.account.user.fetch(userPda)).reputationPoints.toNumber();
+      expect(after - before).to.equal(3 + 5);
+      console.log(`✅ Knowledge bonus + speed bonus claimed: ${before} → ${after}`);
+    });
+
+    it("❌ Should fail to create a speed bonus whose minimum exceeds its maximum", async () => {
+      const members = await knowledgeCommunity("Invalid Speed Bonus", 1);
+
+      await kit.expectError(
+        createKnowledgeVote(members, 1, 1, { speedBonus: { maxBonus: 2, minBonus: 5 } }),
+        "InvalidSpeedBonus"
+      );
+      console.log("✅ Invalid speed bonus correctly rejected");
+    });
+  });
+
+  describe("🙈 HIDDEN KNOWLEDGE RESULTS TESTS", () => {
+    it("✅ Should hide running results until the ballots are revealed", async () => {
+      const members = await knowledgeCommunity("Hidden Results", 2);
+      const vote = await createKnowledgeVote(members, 2, 1);
+      await commitAndClose(members, vote, [1, 1]);
+
+      let voteAccount = await program.account.vote.fetch(vote);
+      expect(voteAccount.totalVotes.toNumber()).to.equal(2);
+      expect(voteAccount.results.map((r) => r.toNumber())).to.deep.equal([0, 0, 0]);
+
+      await revealAnswer(members, vote, 1);
+      await revealBallots(members, vote, [1, 1]);
+
+      voteAccount = await program.account.vote.fetch(vote);
+      expect(voteAccount.results.map((r) => r.toNumber())).to.deep.equal([0, 2, 0]);
+      console.log("✅ Results hidden while sealed and tallied on reveal");
+    });
+
+    it("❌ Should fail to cast an open ballot on a knowledge question", async () => {
+      const members = await knowledgeCommunity("Open Knowledge Ballot", 1);
+      const vote = await createKnowledgeVote(members, 1, 1);
+
+      await kit.expectError(kit.castVoteAs(members.voters[0], vote, members.community, 1), "SealedVoteRequiresCommit");
+      console.log("✅ Open ballot on a knowledge question correctly rejected");
+    });
+  });
+
+  describe("📚 ANSWER EXPLANATION TESTS", () => {
+    const explanation = "Adding two and two gives four.";
+    const explanationUri = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    it("✅ Should store the explanation hash and source reference on reveal", async () => {
+      const members = await knowledgeCommunity("Explained Answer", 1);
+      const vote = await createKnowledgeVote(members, 1, 1);
+      await commitAndClose(members, vote, [1]);
+
+      const explanationHash = sha256(Buffer.from(explanation));
+      await revealAnswer(members, vote, 1, answerSalt, explanationHash, explanationUri);
+
+      const voteAccount = await program.account.vote.fetch(vote);
+      expect(voteAccount.explanationHash).to.deep.equal(explanationHash);
+      expect(voteAccount.explanationUri).to.equal(explanationUri);
+      console.log("✅ Explanation hash and sources stored with the answer");
+    });
+
+    it("❌ Should fail to reference sources without an explanation hash", async () => {
+      const members = await knowledgeCommunity("Unverifiable Sources", 1);
+      const vote = await createKnowledgeVote(members, 1, 1);
+      await commitAndClose(members, vote, [1]);
+
+      await kit.expectError(revealAnswer(members, vote, 1, answerSalt, null, explanationUri), "MissingExplanationHash");
+      console.log("✅ Sources without a verifiable hash correctly rejected");
+    });
+  });
+
+  describe("💳 MEMBERSHIP EXPIRY TESTS", () => {
+    it("❌ Should block an expired member until the dues are renewed", async () => {
+      const admin = await kit.newUser();
+      const member = await kit.newUser();
+      const community = await kit.newCommunity(admin, "Expiring Dues");
+
+      await program.methods
+        .updateCommunity(
+          kit.communitySettings({
+            membershipFee: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            membershipPeriod: new anchor.BN(86_400),
+          }),
+          "Enable dues"
+        )
+        .accounts({
+          community,
+          adminMembership: kit.membershipPdaOf(community, admin.publicKey),
+          moderationLog: await kit.moderationLogPdaOf(community),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      await kit.joinAs(member, community);
+
+      await warp(86_401);
+      await kit.expectError(kit.newOpinionVote(member, community), "MembershipExpired");
+
+      await program.methods
+        .renewMembership(1)
+        .accounts({
+          membership: kit.membershipPdaOf(community, member.publicKey),
+          community,
+          vault: kit.vaultPdaOf(community),
+          member: member.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+
+      // La renovación de una membership vencida cuenta desde ahora
+      const vote = await kit.newOpinionVote(member, community);
+      const voteAccount = await program.account.vote.fetch(vote);
+      expect(voteAccount.creator.toBase58()).to.equal(member.publicKey.toBase58());
+      console.log("✅ Expired member blocked, then allowed again after renewing");
+    });
+  });
 });